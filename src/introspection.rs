@@ -0,0 +1,591 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::{error::Error, fmt};
+
+use super::schema::SchemaError;
+
+// Asks the server which introspection fields it supports, so the schema query
+// only uses fields an older server won't reject.
+pub const CAPABILITIES_QUERY: &str = r#"query CapabilitiesQuery {
+  schema: __type(name: "__Schema") {
+    ...Capabilities
+  }
+  type: __type(name: "__Type") {
+    ...Capabilities
+  }
+  field: __type(name: "__Field") {
+    ...Capabilities
+  }
+  directive: __type(name: "__Directive") {
+    ...Capabilities
+  }
+  inputValue: __type(name: "__InputValue") {
+    ...Capabilities
+  }
+}
+
+fragment Capabilities on __Type {
+  fields {
+    name
+    args {
+      name
+    }
+  }
+}"#;
+
+// How many levels of list and non-null wrappers the schema query fetches, and
+// how many the follow-up queries fetch for types nested deeper than that.
+const TYPE_REF_DEPTH: usize = 8;
+const DEEP_TYPE_REF_DEPTH: usize = 32;
+
+// An entry in the errors array of a GraphQL response.
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ResponseError {
+    pub message: String,
+    pub locations: Option<Vec<Location>>,
+    pub path: Option<Vec<Value>>,
+    pub extensions: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Location {
+    pub line: u32,
+    pub column: u32,
+}
+
+impl fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(location) = self
+            .locations
+            .as_ref()
+            .and_then(|locations| locations.first())
+        {
+            write!(f, " (line {}, column {})", location.line, location.column)?;
+        }
+        if let Some(path) = &self.path {
+            let path: Vec<String> = path
+                .iter()
+                .map(|segment| match segment {
+                    Value::String(name) => name.clone(),
+                    other => other.to_string(),
+                })
+                .collect();
+            write!(f, " at {}", path.join("."))?;
+        }
+        if let Some(extensions) = &self.extensions {
+            match extensions.get("code").and_then(Value::as_str) {
+                Some(code) => write!(f, " [{}]", code)?,
+                None => write!(f, " {}", extensions)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+// Why a server didn't answer an introspection query: the HTTP status when it
+// wasn't successful, and the GraphQL errors it returned.
+#[derive(Debug)]
+pub struct IntrospectionError {
+    pub status: Option<String>,
+    pub errors: Vec<ResponseError>,
+}
+
+impl IntrospectionError {
+    // Reads the GraphQL errors from a response body, if it has any.
+    pub fn from_response(status: Option<String>, text: &str) -> IntrospectionError {
+        let errors = serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|response| response.get("errors").cloned())
+            .and_then(|errors| serde_json::from_value(errors).ok())
+            .unwrap_or_default();
+        IntrospectionError { status, errors }
+    }
+
+    // Whether the server rejected the query itself, rather than failing to
+    // answer it. Servers answer invalid queries with 200 or 400 and errors.
+    pub fn is_validation_error(&self) -> bool {
+        !self.errors.is_empty()
+            && self
+                .status
+                .as_deref()
+                .is_none_or(|status| status.starts_with("400"))
+    }
+}
+
+impl fmt::Display for IntrospectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "introspection failed")?;
+        if let Some(status) = &self.status {
+            write!(f, " with HTTP status {}", status)?;
+        }
+        match self.errors.len() {
+            0 => Ok(()),
+            1 => write!(f, ": {}", self.errors[0]),
+            _ => {
+                write!(f, ":")?;
+                for error in self.errors.iter() {
+                    write!(f, "\n  {}", error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for IntrospectionError {}
+
+// The optional introspection features a server supports. The default supports
+// none of them, which works with servers that predate them all.
+#[derive(Debug, Default, PartialEq)]
+pub struct Capabilities {
+    pub schema_description: bool,
+    // The name of the field, which was renamed from specifiedByUrl.
+    pub specified_by_url: Option<String>,
+    pub is_one_of: bool,
+    pub input_fields_include_deprecated: bool,
+    pub field_args_include_deprecated: bool,
+    pub is_repeatable: bool,
+    pub directive_args_include_deprecated: bool,
+    pub input_value_deprecation: bool,
+}
+
+impl Capabilities {
+    pub fn from_str(text: &str) -> Result<Capabilities, Box<dyn Error>> {
+        let response: Value = serde_json::from_str(text)?;
+        let data = match response.get("data") {
+            Some(data) if data.is_object() => data,
+            _ if response.get("errors").is_some() => {
+                return Err(Box::new(IntrospectionError::from_response(None, text)))
+            }
+            _ => return Err(Box::new(SchemaError::new("data not in response"))),
+        };
+
+        let has_field = |typ: &str, field: &str| field_of(data, typ, field).is_some();
+        let has_arg = |typ: &str, field: &str, arg: &str| {
+            field_of(data, typ, field)
+                .and_then(|field| field.get("args"))
+                .and_then(Value::as_array)
+                .is_some_and(|args| {
+                    args.iter()
+                        .any(|a| a.get("name").and_then(Value::as_str) == Some(arg))
+                })
+        };
+
+        let specified_by_url = ["specifiedByURL", "specifiedByUrl"]
+            .iter()
+            .find(|name| has_field("type", name))
+            .map(|name| name.to_string());
+
+        Ok(Capabilities {
+            schema_description: has_field("schema", "description"),
+            specified_by_url,
+            is_one_of: has_field("type", "isOneOf"),
+            input_fields_include_deprecated: has_arg("type", "inputFields", "includeDeprecated"),
+            field_args_include_deprecated: has_arg("field", "args", "includeDeprecated"),
+            is_repeatable: has_field("directive", "isRepeatable"),
+            directive_args_include_deprecated: has_arg("directive", "args", "includeDeprecated"),
+            input_value_deprecation: has_field("inputValue", "isDeprecated")
+                && has_field("inputValue", "deprecationReason"),
+        })
+    }
+}
+
+fn field_of<'a>(data: &'a Value, typ: &str, field: &str) -> Option<&'a Value> {
+    data.get(typ)
+        .and_then(|typ| typ.get("fields"))
+        .and_then(Value::as_array)
+        .and_then(|fields| {
+            fields
+                .iter()
+                .find(|f| f.get("name").and_then(Value::as_str) == Some(field))
+        })
+}
+
+// Builds the introspection query for the schema, using only the fields the
+// server supports.
+pub fn schema_query(capabilities: &Capabilities) -> String {
+    let include_deprecated = |supported: bool| {
+        if supported {
+            "(includeDeprecated: true)"
+        } else {
+            ""
+        }
+    };
+    let line = |supported: bool, indent: &str, field: &str| {
+        if supported {
+            format!("{}{}\n", indent, field)
+        } else {
+            String::new()
+        }
+    };
+
+    format!(
+        r#"query IntrospectionQuery {{
+  __schema {{
+    queryType {{
+      name
+    }}
+    mutationType {{
+      name
+    }}
+    subscriptionType {{
+      name
+    }}
+    types {{
+      ...FullType
+    }}
+{}    directives {{
+      name
+      description
+{}      locations
+      args{} {{
+        ...InputValue
+      }}
+    }}
+  }}
+}}
+
+fragment FullType on __Type {{
+  kind
+  name
+  description
+{}{}  fields(includeDeprecated: true) {{
+    name
+    description
+    args{} {{
+      ...InputValue
+    }}
+    type {{
+      ...TypeRef
+    }}
+    isDeprecated
+    deprecationReason
+  }}
+  inputFields{} {{
+    ...InputValue
+  }}
+  interfaces {{
+    ...TypeRef
+  }}
+  enumValues(includeDeprecated: true) {{
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }}
+  possibleTypes {{
+    ...TypeRef
+  }}
+}}
+
+fragment InputValue on __InputValue {{
+  name
+  description
+  type {{
+    ...TypeRef
+  }}
+  defaultValue
+{}}}
+
+{}"#,
+        line(capabilities.schema_description, "    ", "description"),
+        line(capabilities.is_repeatable, "      ", "isRepeatable"),
+        include_deprecated(capabilities.directive_args_include_deprecated),
+        match &capabilities.specified_by_url {
+            Some(name) => line(true, "  ", name),
+            None => String::new(),
+        },
+        line(capabilities.is_one_of, "  ", "isOneOf"),
+        include_deprecated(capabilities.field_args_include_deprecated),
+        include_deprecated(capabilities.input_fields_include_deprecated),
+        line(
+            capabilities.input_value_deprecation,
+            "  ",
+            "isDeprecated\n  deprecationReason"
+        ),
+        type_ref_fragment(TYPE_REF_DEPTH)
+    )
+}
+
+// Builds a query for the fields, arguments, and input fields of one type, with
+// enough depth to reach named types the schema query couldn't.
+pub fn type_query(name: &str, capabilities: &Capabilities) -> String {
+    let include_deprecated = if capabilities.field_args_include_deprecated {
+        "(includeDeprecated: true)"
+    } else {
+        ""
+    };
+    let input_fields_include_deprecated = if capabilities.input_fields_include_deprecated {
+        "(includeDeprecated: true)"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"query TypeQuery {{
+  __type(name: "{}") {{
+    name
+    fields(includeDeprecated: true) {{
+      name
+      args{} {{
+        name
+        type {{
+          ...TypeRef
+        }}
+      }}
+      type {{
+        ...TypeRef
+      }}
+    }}
+    inputFields{} {{
+      name
+      type {{
+        ...TypeRef
+      }}
+    }}
+  }}
+}}
+
+{}"#,
+        name,
+        include_deprecated,
+        input_fields_include_deprecated,
+        type_ref_fragment(DEEP_TYPE_REF_DEPTH)
+    )
+}
+
+// Builds a query for the arguments of every directive, with the same depth as
+// type_query.
+pub fn directives_query(capabilities: &Capabilities) -> String {
+    let include_deprecated = if capabilities.directive_args_include_deprecated {
+        "(includeDeprecated: true)"
+    } else {
+        ""
+    };
+
+    format!(
+        r#"query DirectivesQuery {{
+  __schema {{
+    directives {{
+      name
+      args{} {{
+        name
+        type {{
+          ...TypeRef
+        }}
+      }}
+    }}
+  }}
+}}
+
+{}"#,
+        include_deprecated,
+        type_ref_fragment(DEEP_TYPE_REF_DEPTH)
+    )
+}
+
+fn type_ref_fragment(depth: usize) -> String {
+    let mut s = "fragment TypeRef on __Type {\n".to_string();
+    for level in 1..=depth {
+        let indent = "  ".repeat(level);
+        s.push_str(&format!("{}kind\n{}name\n", indent, indent));
+        if level < depth {
+            s.push_str(&format!("{}ofType {{\n", indent));
+        }
+    }
+    for level in (1..depth).rev() {
+        s.push_str(&format!("{}}}\n", "  ".repeat(level)));
+    }
+    s.push('}');
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_introspection_error_should_show_status() {
+        let err = IntrospectionError::from_response(
+            Some("401 Unauthorized".to_string()),
+            "<html>Unauthorized</html>",
+        );
+        assert_eq!(
+            "introspection failed with HTTP status 401 Unauthorized",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_introspection_error_should_show_each_error() {
+        let err = IntrospectionError::from_response(
+            Some("400 Bad Request".to_string()),
+            r#"{
+                "errors": [
+                    {
+                        "message": "Cannot query field \"isOneOf\" on type \"__Type\".",
+                        "locations": [{ "line": 21, "column": 3 }]
+                    },
+                    {
+                        "message": "Not authorized",
+                        "path": ["__schema", "types", 0],
+                        "extensions": { "code": "FORBIDDEN" }
+                    },
+                    {
+                        "message": "Rate limited",
+                        "extensions": { "retryAfter": 30 }
+                    }
+                ]
+            }"#,
+        );
+        assert_eq!(
+            r#"introspection failed with HTTP status 400 Bad Request:
+  Cannot query field "isOneOf" on type "__Type". (line 21, column 3)
+  Not authorized at __schema.types.0 [FORBIDDEN]
+  Rate limited {"retryAfter":30}"#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_introspection_error_should_be_validation_error_only_with_errors_and_200_or_400() {
+        let body = r#"{"errors": [{"message": "Cannot query field \"isOneOf\""}]}"#;
+        assert!(IntrospectionError::from_response(None, body).is_validation_error());
+        assert!(
+            IntrospectionError::from_response(Some("400 Bad Request".to_string()), body)
+                .is_validation_error()
+        );
+        assert!(
+            !IntrospectionError::from_response(Some("401 Unauthorized".to_string()), body)
+                .is_validation_error()
+        );
+        assert!(!IntrospectionError::from_response(
+            Some("400 Bad Request".to_string()),
+            "<html>Bad Request</html>"
+        )
+        .is_validation_error());
+    }
+
+    fn probe_response(type_fields: &str, args_args: &str, input_value_fields: &str) -> String {
+        format!(
+            r#"{{
+                "data": {{
+                    "schema": {{ "fields": [{{ "name": "types", "args": [] }}] }},
+                    "type": {{ "fields": [{}] }},
+                    "field": {{ "fields": [{{ "name": "args", "args": [{}] }}] }},
+                    "directive": {{ "fields": [{{ "name": "args", "args": [{}] }}] }},
+                    "inputValue": {{ "fields": [{}] }}
+                }}
+            }}"#,
+            type_fields, args_args, args_args, input_value_fields
+        )
+    }
+
+    #[test]
+    fn test_capabilities_should_be_none_for_old_server() -> Result<(), Box<dyn Error>> {
+        let response = probe_response(
+            r#"{ "name": "inputFields", "args": [] }"#,
+            "",
+            r#"{ "name": "defaultValue", "args": [] }"#,
+        );
+        assert_eq!(Capabilities::default(), Capabilities::from_str(&response)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_capabilities_should_detect_supported_fields() -> Result<(), Box<dyn Error>> {
+        let response = probe_response(
+            r#"{ "name": "specifiedByUrl", "args": [] },
+               { "name": "inputFields", "args": [{ "name": "includeDeprecated" }] }"#,
+            r#"{ "name": "includeDeprecated" }"#,
+            r#"{ "name": "isDeprecated", "args": [] },
+               { "name": "deprecationReason", "args": [] }"#,
+        );
+        let capabilities = Capabilities::from_str(&response)?;
+        assert_eq!(
+            Capabilities {
+                specified_by_url: Some("specifiedByUrl".to_string()),
+                input_fields_include_deprecated: true,
+                field_args_include_deprecated: true,
+                directive_args_include_deprecated: true,
+                input_value_deprecation: true,
+                ..Capabilities::default()
+            },
+            capabilities
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_capabilities_should_fail_when_no_data() {
+        assert!(Capabilities::from_str(r#"{"errors": [{"message": "no"}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_schema_query_should_use_all_fields_when_supported() {
+        let query = schema_query(&Capabilities {
+            schema_description: true,
+            specified_by_url: Some("specifiedByURL".to_string()),
+            is_one_of: true,
+            input_fields_include_deprecated: true,
+            field_args_include_deprecated: true,
+            is_repeatable: true,
+            directive_args_include_deprecated: true,
+            input_value_deprecation: true,
+        });
+        assert!(query.contains("    description\n    directives {\n"));
+        assert!(query.contains("      isRepeatable\n      locations\n"));
+        assert!(query.contains("  specifiedByURL\n  isOneOf\n"));
+        assert!(query.contains("    args(includeDeprecated: true) {\n"));
+        assert!(query.contains("  inputFields(includeDeprecated: true) {\n"));
+        assert!(query.contains("  defaultValue\n  isDeprecated\n  deprecationReason\n}"));
+    }
+
+    #[test]
+    fn test_type_ref_fragment_should_nest_to_depth() {
+        assert_eq!(
+            "fragment TypeRef on __Type {\n  kind\n  name\n  ofType {\n    kind\n    name\n    ofType {\n      kind\n      name\n    }\n  }\n}",
+            type_ref_fragment(3)
+        );
+        assert_eq!(8, type_ref_fragment(TYPE_REF_DEPTH).matches("kind").count());
+    }
+
+    #[test]
+    fn test_type_query_should_ask_for_one_type() {
+        let query = type_query("Matrix", &Capabilities::default());
+        assert!(query.starts_with("query TypeQuery {\n  __type(name: \"Matrix\") {\n"));
+        assert!(query.contains("      args {\n"));
+        assert_eq!(DEEP_TYPE_REF_DEPTH, query.matches("kind").count());
+    }
+
+    #[test]
+    fn test_directives_query_should_ask_for_directive_args() {
+        let query = directives_query(&Capabilities {
+            directive_args_include_deprecated: true,
+            ..Capabilities::default()
+        });
+        assert!(query.starts_with(
+            "query DirectivesQuery {\n  __schema {\n    directives {\n      name\n      args(includeDeprecated: true) {\n"
+        ));
+        assert_eq!(DEEP_TYPE_REF_DEPTH, query.matches("kind").count());
+    }
+
+    #[test]
+    fn test_schema_query_should_omit_unsupported_fields() {
+        let query = schema_query(&Capabilities::default());
+        for field in [
+            "isRepeatable",
+            "specifiedBy",
+            "isOneOf",
+            "args(includeDeprecated: true)",
+            "inputFields(includeDeprecated: true)",
+        ]
+        .iter()
+        {
+            assert!(!query.contains(field), "query contains {}", field);
+        }
+        assert!(query.contains("  defaultValue\n}\n\nfragment TypeRef on __Type {"));
+        assert!(query.contains("  fields(includeDeprecated: true) {\n"));
+    }
+}
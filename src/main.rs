@@ -1,10 +1,38 @@
+// The original code is written in a style that predates these lints.
+#![allow(
+    clippy::assertions_on_constants,
+    clippy::collapsible_match,
+    clippy::needless_borrow,
+    clippy::single_char_add_str,
+    clippy::single_match,
+    clippy::unnecessary_unwrap,
+    clippy::useless_vec
+)]
+
+mod http;
+mod introspection;
 mod markdown;
 mod schema;
+mod schema_diff;
 mod schema_markdown;
+mod schema_sdl;
+mod sdl;
 
+use http::{GraphQLRequest, HttpOptions, Method, OAuthOptions};
+use reqwest::header::{HeaderName, HeaderValue};
 use schema::Schema;
 use schema_markdown::Markdown;
-use std::{error::Error, fmt, fs::File, io::Write, path::PathBuf};
+use schema_sdl::schema_to_sdl;
+use std::{
+    env,
+    error::Error,
+    fmt,
+    fs::{self, File},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    time::{Duration, SystemTime},
+};
 use structopt::StructOpt;
 
 #[derive(Debug)]
@@ -30,55 +58,258 @@ impl Error for CliError {}
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "gumwood", about = "Convert a GraphQL schema to Markdown")]
-struct Cli {
+enum Cli {
+    #[structopt(about = "Generates markdown for a schema")]
+    Markdown(MarkdownArgs),
+
+    #[structopt(about = "Saves the introspection result from a URL for later use with --json")]
+    Fetch(FetchArgs),
+
+    #[structopt(about = "Prints a schema in another format")]
+    Print(PrintArgs),
+
+    #[structopt(about = "Reports the changes between two versions of a schema")]
+    Diff(DiffArgs),
+}
+
+#[derive(Debug, StructOpt)]
+struct MarkdownArgs {
+    #[structopt(flatten)]
+    source: SourceArgs,
+
+    #[structopt(
+        short,
+        long,
+        help("The output directory for the generated markdown"),
+        parse(from_os_str)
+    )]
+    out_dir: PathBuf,
+
+    #[structopt(short, long, help("Splits output into multiple files"))]
+    multiple: bool,
+
+    #[structopt(
+        long,
+        default_value = "schema.md",
+        help("The output file name when not splitting into multiple files")
+    )]
+    out_file: String,
+
+    #[structopt(
+        short,
+        long,
+        help("Front matter to include at the top of output files")
+    )]
+    front_matter: Option<String>,
+}
+
+#[derive(Debug, StructOpt)]
+struct FetchArgs {
+    #[structopt(short, long, help("The URL to introspect for the GraphQL schema"))]
+    url: String,
+
+    #[structopt(flatten)]
+    http: HttpArgs,
+
+    #[structopt(
+        short,
+        long,
+        help("The file to write the introspection JSON to; - writes stdout"),
+        parse(from_os_str)
+    )]
+    out: PathBuf,
+}
+
+#[derive(Debug, StructOpt)]
+struct PrintArgs {
+    #[structopt(flatten)]
+    source: SourceArgs,
+
+    #[structopt(
+        long,
+        default_value = "sdl",
+        possible_values(&["sdl"]),
+        help("The format to print the schema in")
+    )]
+    format: String,
+
+    #[structopt(
+        long,
+        help("The file to write the schema to instead of stdout"),
+        parse(from_os_str)
+    )]
+    out: Option<PathBuf>,
+}
+
+#[derive(Debug, StructOpt)]
+struct DiffArgs {
+    #[structopt(help("The old schema: a URL, a JSON introspection file, or schema files"))]
+    old: String,
+
+    #[structopt(help("The new schema: a URL, a JSON introspection file, or schema files"))]
+    new: String,
+
+    #[structopt(flatten)]
+    http: HttpArgs,
+
+    #[structopt(
+        long,
+        default_value = "text",
+        possible_values(&["text", "markdown"]),
+        help("The format to report the changes in")
+    )]
+    format: String,
+
+    #[structopt(
+        long,
+        help("The file to write the changes to instead of stdout"),
+        parse(from_os_str)
+    )]
+    out: Option<PathBuf>,
+}
+
+// Where to read the schema from
+#[derive(Debug, StructOpt)]
+struct SourceArgs {
     #[structopt(short, long, help("The URL to introspect for the GraphQL schema"))]
     url: Option<String>,
 
     #[structopt(
         short,
         long,
-        help("The file containing the JSON response of a GraphQL introspection query"),
+        help("The file containing the JSON response of a GraphQL introspection query, optionally gzipped; - reads stdin"),
         parse(from_os_str)
     )]
     json: Option<PathBuf>,
 
-    #[structopt(short, long, help("The GraphQL schema file"), parse(from_os_str))]
-    schema: Option<PathBuf>,
+    #[structopt(
+        short,
+        long,
+        help("The GraphQL schema file, directory, or glob pattern; allows multiple"),
+        parse(from_os_str)
+    )]
+    schema: Vec<PathBuf>,
 
+    #[structopt(flatten)]
+    http: HttpArgs,
+}
+
+// How to request the schema from a URL
+#[derive(Debug, StructOpt)]
+struct HttpArgs {
     #[structopt(
         short,
         long,
-        help("Header to send in name:value format; allows multiple")
+        help("Header to send in name:value format; ${VAR} expands environment variables; allows multiple")
     )]
     header: Vec<String>,
 
     #[structopt(
-        short,
         long,
-        help("The output directory for the generated markdown"),
+        help("File of headers to send, one name:value per line"),
         parse(from_os_str)
     )]
-    out_dir: PathBuf,
+    header_file: Option<PathBuf>,
 
-    #[structopt(short, long, help("Splits output into multiple files"))]
-    multiple: bool,
+    #[structopt(
+        long,
+        help("A PEM or DER CA certificate to trust when connecting to the URL"),
+        parse(from_os_str)
+    )]
+    ca_cert: Option<PathBuf>,
 
     #[structopt(
-        short,
         long,
-        help("Front matter to include at the top of output files")
+        help("A PKCS#12 file with the client certificate and key to send to the URL"),
+        parse(from_os_str)
     )]
-    front_matter: Option<String>,
+    client_cert: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        help("The password for the client certificate; ${VAR} expands environment variables")
+    )]
+    client_cert_password: Option<String>,
+
+    #[structopt(long, help("Skips verifying the URL's TLS certificate"))]
+    insecure: bool,
+
+    #[structopt(long, help("The HTTP or HTTPS proxy to connect to the URL through"))]
+    proxy: Option<String>,
+
+    #[structopt(long, help("Seconds to wait for each request to the URL"))]
+    timeout: Option<u64>,
+
+    #[structopt(
+        long,
+        default_value = "0",
+        help("Times to retry requests to the URL after connection errors and 5xx responses")
+    )]
+    retries: u32,
+
+    #[structopt(long, help("The OAuth2 token endpoint to get an access token from"))]
+    token_url: Option<String>,
+
+    #[structopt(
+        long,
+        help("The OAuth2 client ID; ${VAR} expands environment variables")
+    )]
+    client_id: Option<String>,
+
+    #[structopt(
+        long,
+        help("The OAuth2 client secret; ${VAR} expands environment variables")
+    )]
+    client_secret: Option<String>,
+
+    #[structopt(
+        long,
+        help("A file containing the OAuth2 client secret"),
+        parse(from_os_str)
+    )]
+    client_secret_file: Option<PathBuf>,
+
+    #[structopt(long, help("An OAuth2 scope to request; allows multiple"))]
+    scope: Vec<String>,
+
+    #[structopt(
+        long,
+        help("A file containing the introspection query to send instead of the built-in one"),
+        parse(from_os_str)
+    )]
+    introspection_query: Option<PathBuf>,
+
+    #[structopt(long, help("The operation to run from the introspection query file"))]
+    operation_name: Option<String>,
+
+    #[structopt(long, help("JSON variables for the introspection query file"))]
+    variables: Option<String>,
+
+    #[structopt(
+        long,
+        default_value = "POST",
+        possible_values(&["GET", "POST"]),
+        case_insensitive(true),
+        help("The HTTP method for requests to the URL")
+    )]
+    method: Method,
+
+    #[structopt(long, help("Sends queries as Automatic Persisted Queries"))]
+    persisted_queries: bool,
 }
 
-fn get_schema(args: &Cli) -> Result<Schema, Box<dyn Error>> {
+fn get_schema(args: &SourceArgs) -> Result<Schema, Box<dyn Error>> {
     let schema: Schema;
     if args.url.is_some() {
-        schema = Schema::from_url(&args.url.as_ref().unwrap(), &args.header)?;
+        schema = Schema::from_url(
+            &args.url.as_ref().unwrap(),
+            &get_http_options(&args.http)?,
+            get_introspection_query(&args.http)?.as_ref(),
+        )?;
     } else if args.json.is_some() {
         schema = Schema::from_json(&args.json.as_ref().unwrap())?;
-    } else if args.schema.is_some() {
-        schema = Schema::from_schema(&args.schema.as_ref().unwrap())?;
+    } else if !args.schema.is_empty() {
+        schema = Schema::from_schema(&args.schema)?;
     } else {
         return Err(Box::new(CliError::new(
             "you must specify url, json, or schema",
@@ -88,26 +319,345 @@ fn get_schema(args: &Cli) -> Result<Schema, Box<dyn Error>> {
     Ok(schema)
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
+fn get_http_options(args: &HttpArgs) -> Result<HttpOptions, Box<dyn Error>> {
+    let client_cert_password = match &args.client_cert_password {
+        Some(password) => Some(
+            expand_env(password, &|name| env::var(name).ok())
+                .map_err(|err| CliError::new(&format!("client cert password: {}", err)))?,
+        ),
+        None => None,
+    };
+
+    Ok(HttpOptions {
+        headers: get_headers(args)?,
+        ca_cert: args.ca_cert.clone(),
+        client_cert: args.client_cert.clone(),
+        client_cert_password,
+        insecure: args.insecure,
+        proxy: args.proxy.clone(),
+        timeout: args.timeout.map(Duration::from_secs),
+        retries: args.retries,
+        oauth: get_oauth_options(args)?,
+        method: args.method,
+        persisted_queries: args.persisted_queries,
+        ..HttpOptions::default()
+    })
+}
+
+fn get_introspection_query(args: &HttpArgs) -> Result<Option<GraphQLRequest>, Box<dyn Error>> {
+    let file = match &args.introspection_query {
+        Some(file) => file,
+        None => {
+            if args.operation_name.is_some() || args.variables.is_some() {
+                return Err(Box::new(CliError::new(
+                    "--operation-name and --variables require --introspection-query",
+                )));
+            }
+            return Ok(None);
+        }
+    };
+
+    let query = fs::read_to_string(file)
+        .map_err(|err| CliError::new(&format!("{}: {}", file.display(), err)))?;
+    let mut request = GraphQLRequest::new(&query);
+    if let Some(operation_name) = &args.operation_name {
+        request.operation_name = Some(operation_name.to_string());
+    }
+    if let Some(variables) = &args.variables {
+        let variables: serde_json::Value = serde_json::from_str(variables)
+            .map_err(|err| CliError::new(&format!("invalid variables: {}", err)))?;
+        if !variables.is_object() {
+            return Err(Box::new(CliError::new(
+                "invalid variables: must be a JSON object",
+            )));
+        }
+        request.variables = Some(variables);
+    }
+    Ok(Some(request))
+}
+
+fn get_oauth_options(args: &HttpArgs) -> Result<Option<OAuthOptions>, Box<dyn Error>> {
+    let token_url = match &args.token_url {
+        Some(token_url) => token_url,
+        None => {
+            if args.client_id.is_some()
+                || args.client_secret.is_some()
+                || args.client_secret_file.is_some()
+                || !args.scope.is_empty()
+            {
+                return Err(Box::new(CliError::new(
+                    "OAuth2 options require --token-url",
+                )));
+            }
+            return Ok(None);
+        }
+    };
+
+    let client_id = match &args.client_id {
+        Some(client_id) => expand_env(client_id, &|name| env::var(name).ok())
+            .map_err(|err| CliError::new(&format!("client id: {}", err)))?,
+        None => return Err(Box::new(CliError::new("--token-url requires --client-id"))),
+    };
+    let client_secret = match (&args.client_secret, &args.client_secret_file) {
+        (Some(secret), None) => expand_env(secret, &|name| env::var(name).ok())
+            .map_err(|err| CliError::new(&format!("client secret: {}", err)))?,
+        (None, Some(file)) => fs::read_to_string(file)
+            .map_err(|err| CliError::new(&format!("{}: {}", file.display(), err)))?
+            .trim()
+            .to_string(),
+        (Some(_), Some(_)) => {
+            return Err(Box::new(CliError::new(
+                "specify only one of --client-secret and --client-secret-file",
+            )))
+        }
+        (None, None) => {
+            return Err(Box::new(CliError::new(
+                "--token-url requires --client-secret or --client-secret-file",
+            )))
+        }
+    };
+
+    Ok(Some(OAuthOptions {
+        token_url: token_url.to_string(),
+        client_id,
+        client_secret,
+        scopes: args.scope.clone(),
+    }))
+}
+
+// Collects the headers from --header-file followed by --header. Blank lines
+// and lines starting with # in the header file are skipped.
+fn get_headers(args: &HttpArgs) -> Result<Vec<(String, String)>, Box<dyn Error>> {
+    let mut headers = Vec::new();
+    if let Some(file) = &args.header_file {
+        let contents = fs::read_to_string(file)
+            .map_err(|err| CliError::new(&format!("{}: {}", file.display(), err)))?;
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let header = parse_header(line, &|name| env::var(name).ok()).map_err(|err| {
+                CliError::new(&format!("{}:{}: {}", file.display(), number + 1, err))
+            })?;
+            headers.push(header);
+        }
+    }
+    for header in &args.header {
+        headers.push(parse_header(header, &|name| env::var(name).ok())?);
+    }
+    Ok(headers)
+}
+
+// Splits a header on its first colon, so values may contain colons, and
+// expands ${VAR} references in the value.
+fn parse_header(
+    header: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> Result<(String, String), CliError> {
+    let (name, value) = match header.find(':') {
+        Some(index) => (header[..index].trim(), header[index + 1..].trim()),
+        None => {
+            return Err(CliError::new(&format!(
+                "header \"{}\" must be in name:value format",
+                header
+            )))
+        }
+    };
+    if name.is_empty() || HeaderName::from_bytes(name.as_bytes()).is_err() {
+        return Err(CliError::new(&format!(
+            "header \"{}\" has an invalid name",
+            header
+        )));
+    }
+
+    let value = expand_env(value, lookup)
+        .map_err(|err| CliError::new(&format!("header \"{}\": {}", name, err)))?;
+    if HeaderValue::from_str(&value).is_err() {
+        return Err(CliError::new(&format!(
+            "header \"{}\" has an invalid value",
+            name
+        )));
+    }
+    Ok((name.to_string(), value))
+}
+
+fn expand_env(value: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String, String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        expanded.push_str(&rest[..start]);
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => return Err("unclosed ${ in value".to_string()),
+        };
+        let name = &rest[start + 2..end];
+        match lookup(name) {
+            Some(var) => expanded.push_str(&var),
+            None => return Err(format!("environment variable {} is not set", name)),
+        }
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+fn main() {
     let args = Cli::from_args();
 
-    let schema = get_schema(&args)?;
+    if let Err(err) = run(args) {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    }
+}
+
+fn run(args: Cli) -> Result<(), Box<dyn Error>> {
+    match args {
+        Cli::Markdown(args) => markdown(args),
+        Cli::Fetch(args) => fetch(args),
+        Cli::Print(args) => print(args),
+        Cli::Diff(args) => diff(args),
+    }
+}
+
+fn markdown(args: MarkdownArgs) -> Result<(), Box<dyn Error>> {
+    let schema = get_schema(&args.source)?;
+    for truncated in schema.get_truncated() {
+        eprintln!(
+            "warning: the type of {} is nested too deeply to resolve; it is shown with ?",
+            truncated
+        );
+    }
     let markdown = Markdown::with_front_matter(args.front_matter)?;
-    let contents = markdown.generate_from_schema(&schema);
-    for (name, markdown) in contents {
-        if !markdown.is_empty() {
-            let out_file = format!("{}.md", name);
-            let mut file = File::create(&args.out_dir.join(out_file))?;
-            file.write_all(markdown.as_bytes())?;
+    if args.multiple {
+        let contents = markdown.generate_from_schema(&schema);
+        for (name, markdown) in contents {
+            if !markdown.is_empty() {
+                write_file(&args.out_dir.join(format!("{}.md", name)), &markdown)?;
+            }
+        }
+    } else {
+        let name = Path::new(&args.out_file)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("schema");
+        let contents = markdown.generate_single_from_schema(&schema, name);
+        write_file(&args.out_dir.join(&args.out_file), &contents)?;
+    }
+
+    Ok(())
+}
+
+fn fetch(args: FetchArgs) -> Result<(), Box<dyn Error>> {
+    let mut schema = Schema::from_url(
+        &args.url,
+        &get_http_options(&args.http)?,
+        get_introspection_query(&args.http)?.as_ref(),
+    )?;
+    for truncated in schema.get_truncated() {
+        eprintln!(
+            "warning: the type of {} is nested too deeply to resolve; it is saved with ?",
+            truncated
+        );
+    }
+    schema.normalize();
+    let json = schema.to_introspection_json(&args.url, SystemTime::now())?;
+    if args.out == Path::new("-") {
+        io::stdout().write_all(json.as_bytes())?;
+        Ok(())
+    } else {
+        write_file(&args.out, &json)
+    }
+}
+
+fn print(args: PrintArgs) -> Result<(), Box<dyn Error>> {
+    let schema = get_schema(&args.source)?;
+    // A type printed as ? wouldn't parse, so there's no SDL to print.
+    let truncated = schema.get_truncated();
+    if !truncated.is_empty() {
+        return Err(Box::new(CliError::new(&format!(
+            "the types of {} are nested too deeply to resolve, so they can't be printed as SDL",
+            truncated.join(", ")
+        ))));
+    }
+    let printed = match args.format.as_str() {
+        "sdl" => schema_to_sdl(&schema),
+        format => {
+            return Err(Box::new(CliError::new(&format!(
+                "unsupported format {}",
+                format
+            ))))
+        }
+    };
+    match &args.out {
+        Some(out) => write_file(out, &printed),
+        None => {
+            io::stdout().write_all(printed.as_bytes())?;
+            Ok(())
+        }
+    }
+}
+
+fn diff(args: DiffArgs) -> Result<(), Box<dyn Error>> {
+    let old = get_diff_schema(&args.old, &args.http)?;
+    let new = get_diff_schema(&args.new, &args.http)?;
+    let changes = schema_diff::diff(&old, &new);
+    let report = match args.format.as_str() {
+        "text" => schema_diff::changes_to_text(&changes),
+        "markdown" => schema_diff::changes_to_markdown(&changes),
+        format => {
+            return Err(Box::new(CliError::new(&format!(
+                "unsupported format {}",
+                format
+            ))))
+        }
+    };
+    match &args.out {
+        Some(out) => write_file(out, &report),
+        None => {
+            io::stdout().write_all(report.as_bytes())?;
+            Ok(())
         }
     }
+}
 
+// Loads a schema to diff, telling the kind of source from its form: URLs
+// start with http:// or https://, introspection results end with .json or
+// .json.gz or are - for stdin, and anything else is schema files.
+fn get_diff_schema(source: &str, http: &HttpArgs) -> Result<Schema, Box<dyn Error>> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        Schema::from_url(
+            source,
+            &get_http_options(http)?,
+            get_introspection_query(http)?.as_ref(),
+        )
+        .map_err(|err| CliError::new(&format!("{}: {}", source, err)).into())
+    } else if source == "-" || source.ends_with(".json") || source.ends_with(".json.gz") {
+        Schema::from_json(Path::new(source))
+    } else {
+        Schema::from_schema(&[PathBuf::from(source)])
+    }
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), Box<dyn Error>> {
+    let mut file = File::create(path)?;
+    file.write_all(contents.as_bytes())?;
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::schema::tests::TempDir;
+
+    fn markdown_args(args: &[&str]) -> MarkdownArgs {
+        let mut args = args.to_vec();
+        args.insert(1, "markdown");
+        match Cli::from_iter(args.iter()) {
+            Cli::Markdown(args) => args,
+            command => panic!("expected markdown, got {:?}", command),
+        }
+    }
 
     #[test]
     fn test_it_should_return_ok_when_url_specified() -> Result<(), String> {
@@ -125,11 +675,11 @@ mod tests {
             "--front-matter",
             "a:b;c:d",
         ];
-        let args = Cli::from_iter(vec.iter());
-        assert_eq!(args.url.unwrap(), "https://example.com");
-        assert_eq!(args.header.len(), 2);
-        assert_eq!(args.header[0], "name1:value1");
-        assert_eq!(args.header[1], "name2:value2");
+        let args = markdown_args(&vec);
+        assert_eq!(args.source.url.unwrap(), "https://example.com");
+        assert_eq!(args.source.http.header.len(), 2);
+        assert_eq!(args.source.http.header[0], "name1:value1");
+        assert_eq!(args.source.http.header[1], "name2:value2");
         assert_eq!(args.out_dir.as_path().display().to_string(), "./out");
         assert_eq!(args.front_matter.unwrap(), "a:b;c:d");
         assert!(args.multiple);
@@ -152,11 +702,11 @@ mod tests {
             "--front-matter",
             "a:b;c:d",
         ];
-        let args = Cli::from_iter(vec.iter());
-        assert_eq!(args.json.unwrap().display().to_string(), "foo.json");
-        assert_eq!(args.header.len(), 2);
-        assert_eq!(args.header[0], "name1:value1");
-        assert_eq!(args.header[1], "name2:value2");
+        let args = markdown_args(&vec);
+        assert_eq!(args.source.json.unwrap().display().to_string(), "foo.json");
+        assert_eq!(args.source.http.header.len(), 2);
+        assert_eq!(args.source.http.header[0], "name1:value1");
+        assert_eq!(args.source.http.header[1], "name2:value2");
         assert_eq!(args.out_dir.as_path().display().to_string(), "./out");
         assert_eq!(args.front_matter.unwrap(), "a:b;c:d");
         assert!(args.multiple);
@@ -179,17 +729,43 @@ mod tests {
             "--front-matter",
             "a:b;c:d",
         ];
-        let args = Cli::from_iter(vec.iter());
-        assert_eq!(args.schema.unwrap().display().to_string(), "schema.graphql");
-        assert_eq!(args.header.len(), 2);
-        assert_eq!(args.header[0], "name1:value1");
-        assert_eq!(args.header[1], "name2:value2");
+        let args = markdown_args(&vec);
+        assert_eq!(args.source.schema.len(), 1);
+        assert_eq!(
+            args.source.schema[0].display().to_string(),
+            "schema.graphql"
+        );
+        assert_eq!(args.source.http.header.len(), 2);
+        assert_eq!(args.source.http.header[0], "name1:value1");
+        assert_eq!(args.source.http.header[1], "name2:value2");
         assert_eq!(args.out_dir.as_path().display().to_string(), "./out");
         assert_eq!(args.front_matter.unwrap(), "a:b;c:d");
         assert!(args.multiple);
         Ok(())
     }
 
+    #[test]
+    fn test_it_should_return_ok_when_multiple_schemas_specified() {
+        let vec = [
+            "gumroad",
+            "--schema",
+            "schema.graphql",
+            "-s",
+            "schema/",
+            "--schema",
+            "domains/**/*.graphql",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        assert_eq!(args.source.schema.len(), 3);
+        assert_eq!(args.source.schema[1].display().to_string(), "schema/");
+        assert_eq!(
+            args.source.schema[2].display().to_string(),
+            "domains/**/*.graphql"
+        );
+    }
+
     #[test]
     fn test_multiple_false() -> Result<(), String> {
         let vec = vec![
@@ -199,15 +775,519 @@ mod tests {
             "--out-dir",
             "./out",
         ];
-        let args = Cli::from_iter(vec.iter());
+        let args = markdown_args(&vec);
         assert!(!args.multiple);
+        assert_eq!(args.out_file, "schema.md");
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_file_should_be_settable() {
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--out-dir",
+            "./out",
+            "--out-file",
+            "api.md",
+        ];
+        let args = markdown_args(&vec);
+        assert_eq!(args.out_file, "api.md");
+    }
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "TOKEN" => Some("abc:def".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_parse_header_should_split_on_first_colon() -> Result<(), CliError> {
+        let header = parse_header("Authorization: Bearer abc:def", &lookup)?;
+        assert_eq!(
+            header,
+            ("Authorization".to_string(), "Bearer abc:def".to_string())
+        );
+        let header = parse_header("X-Origin:https://example.com", &lookup)?;
+        assert_eq!(
+            header,
+            ("X-Origin".to_string(), "https://example.com".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_header_should_expand_env() -> Result<(), CliError> {
+        let header = parse_header("Authorization: Bearer ${TOKEN}", &lookup)?;
+        assert_eq!(header.1, "Bearer abc:def");
         Ok(())
     }
 
+    #[test]
+    fn test_parse_header_should_fail_when_malformed() {
+        let cases = [
+            (
+                "Authorization",
+                "header \"Authorization\" must be in name:value format",
+            ),
+            (": value", "header \": value\" has an invalid name"),
+            (
+                "Bad Name: value",
+                "header \"Bad Name: value\" has an invalid name",
+            ),
+            (
+                "Authorization: ${MISSING}",
+                "header \"Authorization\": environment variable MISSING is not set",
+            ),
+            (
+                "Authorization: ${TOKEN",
+                "header \"Authorization\": unclosed ${ in value",
+            ),
+            (
+                "X-Value: a\u{7}b",
+                "header \"X-Value\" has an invalid value",
+            ),
+        ];
+        for (header, message) in cases.iter() {
+            match parse_header(header, &lookup) {
+                Ok(_) => panic!("{} should not parse", header),
+                Err(err) => assert_eq!(err.to_string(), *message),
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_headers_should_read_header_file() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("get_headers_should_read_header_file");
+        let file = dir.join("headers.txt");
+        fs::write(&file, "# auth\nAuthorization: Bearer abc\n\nX-One: 1\n")?;
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--header-file",
+            file.to_str().unwrap(),
+            "--header",
+            "X-Two: 2",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        let headers = get_headers(&args.source.http)?;
+        let names: Vec<&str> = headers.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, vec!["Authorization", "X-One", "X-Two"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_headers_should_report_line_in_header_file() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("get_headers_should_report_line_in_header_file");
+        let file = dir.join("bad_headers.txt");
+        fs::write(&file, "X-One: 1\nbroken\n")?;
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--header-file",
+            file.to_str().unwrap(),
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        let result = get_headers(&args.source.http);
+        match result {
+            Ok(_) => panic!("headers should not parse"),
+            Err(err) => assert!(err
+                .to_string()
+                .ends_with("bad_headers.txt:2: header \"broken\" must be in name:value format")),
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_http_options_should_map_transport_args() -> Result<(), Box<dyn Error>> {
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--ca-cert",
+            "ca.pem",
+            "--client-cert",
+            "client.p12",
+            "--client-cert-password",
+            "secret",
+            "--insecure",
+            "--proxy",
+            "http://proxy:3128",
+            "--timeout",
+            "30",
+            "--retries",
+            "3",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        let options = get_http_options(&args.source.http)?;
+        assert_eq!(options.ca_cert, Some(PathBuf::from("ca.pem")));
+        assert_eq!(options.client_cert, Some(PathBuf::from("client.p12")));
+        assert_eq!(options.client_cert_password.as_deref(), Some("secret"));
+        assert!(options.insecure);
+        assert_eq!(options.proxy.as_deref(), Some("http://proxy:3128"));
+        assert_eq!(options.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.retries, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_http_options_should_default_to_no_retries() -> Result<(), Box<dyn Error>> {
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        let options = get_http_options(&args.source.http)?;
+        assert!(!options.insecure);
+        assert_eq!(options.timeout, None);
+        assert_eq!(options.retries, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_oauth_options_should_read_secret_file() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("get_oauth_options_should_read_secret_file");
+        let file = dir.join("client_secret.txt");
+        fs::write(&file, "s3cret\n")?;
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--token-url",
+            "https://auth.example.com/token",
+            "--client-id",
+            "gumwood",
+            "--client-secret-file",
+            file.to_str().unwrap(),
+            "--scope",
+            "schema:read",
+            "--scope",
+            "docs",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        let oauth = get_oauth_options(&args.source.http);
+        let oauth = oauth?.unwrap();
+        assert_eq!(oauth.token_url, "https://auth.example.com/token");
+        assert_eq!(oauth.client_id, "gumwood");
+        assert_eq!(oauth.client_secret, "s3cret");
+        assert_eq!(oauth.scopes, vec!["schema:read", "docs"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_oauth_options_should_be_none_without_token_url() -> Result<(), Box<dyn Error>> {
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        assert!(get_oauth_options(&args.source.http)?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_oauth_options_should_fail_when_incomplete() {
+        let cases: [(&[&str], &str); 3] = [
+            (
+                &["--client-id", "gumwood"],
+                "OAuth2 options require --token-url",
+            ),
+            (
+                &[
+                    "--token-url",
+                    "https://auth.example.com/token",
+                    "--client-secret",
+                    "s",
+                ],
+                "--token-url requires --client-id",
+            ),
+            (
+                &[
+                    "--token-url",
+                    "https://auth.example.com/token",
+                    "--client-id",
+                    "gumwood",
+                ],
+                "--token-url requires --client-secret or --client-secret-file",
+            ),
+        ];
+        for (extra, message) in cases.iter() {
+            let mut vec = vec![
+                "gumroad",
+                "--url",
+                "https://example.com",
+                "--out-dir",
+                "./out",
+            ];
+            vec.extend_from_slice(extra);
+            let args = markdown_args(&vec);
+            match get_oauth_options(&args.source.http) {
+                Ok(_) => panic!("{:?} should not parse", extra),
+                Err(err) => assert_eq!(err.to_string(), *message),
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_introspection_query_should_read_file() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("get_introspection_query_should_read_file");
+        let file = dir.join("introspection.graphql");
+        fs::write(
+            &file,
+            "query Docs($v: Int) { __schema { description } }\nquery Other { a }\n",
+        )?;
+        let vec = [
+            "gumroad",
+            "--url",
+            "https://example.com",
+            "--introspection-query",
+            file.to_str().unwrap(),
+            "--operation-name",
+            "Other",
+            "--variables",
+            r#"{"v": 1}"#,
+            "--method",
+            "get",
+            "--persisted-queries",
+            "--out-dir",
+            "./out",
+        ];
+        let args = markdown_args(&vec);
+        let request = get_introspection_query(&args.source.http);
+        let request = request?.unwrap();
+        assert!(request.query.starts_with("query Docs($v: Int)"));
+        assert_eq!(request.operation_name.as_deref(), Some("Other"));
+        assert_eq!(request.variables, Some(serde_json::json!({"v": 1})));
+
+        let options = get_http_options(&args.source.http)?;
+        assert_eq!(options.method, Method::Get);
+        assert!(options.persisted_queries);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_introspection_query_should_fail_when_invalid() {
+        let cases: [(&[&str], &str); 3] = [
+            (
+                &["--operation-name", "Docs"],
+                "--operation-name and --variables require --introspection-query",
+            ),
+            (
+                &["--introspection-query", "Cargo.toml", "--variables", "{"],
+                "invalid variables: EOF while parsing an object at line 1 column 1",
+            ),
+            (
+                &["--introspection-query", "Cargo.toml", "--variables", "[]"],
+                "invalid variables: must be a JSON object",
+            ),
+        ];
+        for (extra, message) in cases.iter() {
+            let mut vec = vec![
+                "gumroad",
+                "--url",
+                "https://example.com",
+                "--out-dir",
+                "./out",
+            ];
+            vec.extend_from_slice(extra);
+            let args = markdown_args(&vec);
+            match get_introspection_query(&args.source.http) {
+                Ok(_) => panic!("{:?} should not parse", extra),
+                Err(err) => assert_eq!(err.to_string(), *message),
+            }
+        }
+    }
+
     #[test]
     fn test_get_schema_should_return_error_when_none_specified() {
         let vec = vec!["gumroad", "--out-dir", "./out"];
-        let args = Cli::from_iter(vec.iter());
-        assert!(get_schema(&args).is_err());
+        let args = markdown_args(&vec);
+        assert!(get_schema(&args.source).is_err());
+    }
+
+    #[test]
+    fn test_fetch_should_require_url_and_out() {
+        let vec = ["gumroad", "fetch", "--out", "schema.json"];
+        assert!(Cli::from_iter_safe(vec.iter()).is_err());
+
+        let vec = [
+            "gumroad",
+            "fetch",
+            "--url",
+            "https://example.com",
+            "--header",
+            "name1:value1",
+            "--out",
+            "schema.json",
+        ];
+        match Cli::from_iter(vec.iter()) {
+            Cli::Fetch(args) => {
+                assert_eq!(args.url, "https://example.com");
+                assert_eq!(args.http.header, vec!["name1:value1"]);
+                assert_eq!(args.out.display().to_string(), "schema.json");
+            }
+            command => panic!("expected fetch, got {:?}", command),
+        }
+    }
+
+    #[test]
+    fn test_fetch_should_write_json_for_markdown() -> Result<(), Box<dyn Error>> {
+        let url = crate::http::tests::serve(vec![
+            ("200 OK", r#"{"data": {"__schema": {}}}"#),
+            (
+                "200 OK",
+                r#"{"data": {"__schema": {"queryType": {"name": "Query"}, "types": [
+                    {"kind": "OBJECT", "name": "Query", "fields": [
+                        {"name": "b", "args": [], "type": {"kind": "SCALAR", "name": "Int"}},
+                        {"name": "a", "args": [], "type": {"kind": "SCALAR", "name": "Int"}}
+                    ]}
+                ]}}}"#,
+            ),
+        ]);
+        let dir = TempDir::new("fetch_should_write_json_for_markdown");
+        let out = dir.join("fetch.json");
+        let vec = [
+            "gumroad",
+            "fetch",
+            "--url",
+            &url,
+            "--out",
+            out.to_str().unwrap(),
+        ];
+        run(Cli::from_iter(vec.iter()))?;
+        let json = fs::read_to_string(&out)?;
+
+        let response: serde_json::Value = serde_json::from_str(&json)?;
+        assert_eq!(url, response["extensions"]["gumwood"]["source"]);
+        let fields = &response["data"]["__schema"]["types"][0]["fields"];
+        assert_eq!("a", fields[0]["name"]);
+        assert_eq!("b", fields[1]["name"]);
+        assert_eq!("Query", Schema::from_str(&json)?.get_query_name().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_should_write_sdl() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("print_should_write_sdl");
+        let out = dir.join("print.graphql");
+        let vec = [
+            "gumroad",
+            "print",
+            "--schema",
+            "testdata/schema.graphql",
+            "--format",
+            "sdl",
+            "--out",
+            out.to_str().unwrap(),
+        ];
+        run(Cli::from_iter(vec.iter()))?;
+        let sdl = fs::read_to_string(&out)?;
+        assert!(sdl.starts_with(
+            "directive @cached(ttl: Int = 60) on FIELD_DEFINITION\n\n\"\"\"The root query\"\"\"\ntype Query {\n"
+        ));
+        assert!(sdl.ends_with("\n\nscalar DateTime\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_print_should_fail_when_types_truncated() {
+        let dir = TempDir::new("print_should_fail_when_types_truncated");
+        let json = dir.join("truncated.json");
+        fs::write(
+            &json,
+            r#"{"__schema": {"types": [
+                {"kind": "OBJECT", "name": "Matrix", "fields": [
+                    {"name": "rows", "args": [], "type": {"kind": "NON_NULL", "name": null, "ofType": null}}
+                ]}
+            ]}}"#,
+        )
+        .unwrap();
+        let out = dir.join("print.graphql");
+        let vec = [
+            "gumroad",
+            "print",
+            "--json",
+            json.to_str().unwrap(),
+            "--out",
+            out.to_str().unwrap(),
+        ];
+        match run(Cli::from_iter(vec.iter())) {
+            Ok(_) => panic!("truncated types should not print"),
+            Err(err) => assert_eq!(
+                "the types of Matrix.rows are nested too deeply to resolve, so they can't be printed as SDL",
+                err.to_string()
+            ),
+        }
+        assert!(!out.exists());
+    }
+
+    #[test]
+    fn test_diff_should_report_changes_between_sources() -> Result<(), Box<dyn Error>> {
+        let dir = TempDir::new("diff_should_report_changes_between_sources");
+        let old = dir.join("old.graphql");
+        fs::write(&old, "type Query { a: Int b: Int }\n")?;
+        let new = dir.join("new.json");
+        fs::write(
+            &new,
+            r#"{"data": {"__schema": {"types": [
+                {"kind": "OBJECT", "name": "Query", "fields": [
+                    {"name": "a", "args": [], "type": {"kind": "NON_NULL", "name": null, "ofType": {"kind": "SCALAR", "name": "Int"}}}
+                ]}
+            ]}}}"#,
+        )?;
+        let out = dir.join("diff.md");
+        let vec = [
+            "gumroad",
+            "diff",
+            old.to_str().unwrap(),
+            new.to_str().unwrap(),
+            "--format",
+            "markdown",
+            "--out",
+            out.to_str().unwrap(),
+        ];
+        run(Cli::from_iter(vec.iter()))?;
+        let report = fs::read_to_string(&out)?;
+        assert_eq!(
+            "# Schema Changes\n\n## Breaking\n\n| Path | Change |\n| --- | --- |\n| `Query.b` | Field was removed |\n\n## Safe\n\n| Path | Change |\n| --- | --- |\n| `Query.a` | Type changed from Int to Int! |\n\n",
+            report
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_diff_schema_should_name_failing_source() {
+        let vec = ["gumroad", "diff", "old.graphql", "new.graphql"];
+        if let Cli::Diff(args) = Cli::from_iter(vec.iter()) {
+            match get_diff_schema("testdata/missing.json", &args.http) {
+                Ok(_) => panic!("schema should not load"),
+                Err(err) => assert!(err.to_string().starts_with("testdata/missing.json: ")),
+            }
+            // Nothing listens on port 9
+            match get_diff_schema("http://127.0.0.1:9/graphql", &args.http) {
+                Ok(_) => panic!("schema should not load"),
+                Err(err) => assert!(err.to_string().starts_with("http://127.0.0.1:9/graphql: ")),
+            }
+        } else {
+            panic!("expected diff");
+        }
     }
 }
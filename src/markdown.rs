@@ -19,6 +19,46 @@ pub fn to_list(items: &[&str]) -> String {
     format!("{}\n", list)
 }
 
+pub fn to_list_item(depth: usize, item: &str) -> String {
+    format!("{}* {}\n", "  ".repeat(depth), item)
+}
+
+pub fn to_link(text: &str, target: &str) -> String {
+    format!("[{}]({})", text, target)
+}
+
+pub fn to_anchor(id: &str) -> String {
+    format!("{}\n\n", to_inline_anchor(id))
+}
+
+pub fn to_inline_anchor(id: &str) -> String {
+    format!("<a id=\"{}\"></a>", id)
+}
+
+// Writes each value as a double-quoted string, which is valid YAML whatever
+// the value contains. JSON string escapes are valid YAML escapes.
+pub fn to_front_matter(items: &[(String, String)]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+
+    let fields: String = items
+        .iter()
+        .map(|(key, value)| {
+            format!(
+                "{}: {}\n",
+                key,
+                serde_json::Value::String(value.to_string())
+            )
+        })
+        .collect();
+    format!("---\n{}---\n\n", fields)
+}
+
+pub fn to_code_block(language: &str, code: &str) -> String {
+    format!("```{}\n{}\n```\n\n", language, code.trim_end())
+}
+
 pub fn to_notice(notice: &str) -> String {
     format!("_{}_\n", notice)
 }
@@ -42,6 +82,14 @@ mod tests {
         assert_eq!("# My Header\n\n", to_header(1, "My Header"));
     }
 
+    #[test]
+    fn test_to_code_block_should_fence_code() {
+        assert_eq!(
+            "```graphql\nscalar Date\n```\n\n",
+            to_code_block("graphql", "scalar Date\n")
+        );
+    }
+
     #[test]
     fn test_to_header_should_create_header_6() {
         assert_eq!("###### My Header\n\n", to_header(6, "My Header"));
@@ -60,6 +108,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_front_matter_should_return_empty_when_no_items() {
+        assert_eq!("", to_front_matter(&[]));
+    }
+
+    #[test]
+    fn test_to_front_matter_should_create_yaml_block() {
+        assert_eq!(
+            "---\nid: \"queries\"\ntitle: \"Query\"\n---\n\n",
+            to_front_matter(&[
+                ("id".to_string(), "queries".to_string()),
+                ("title".to_string(), "Query".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_front_matter_should_quote_values() {
+        assert_eq!(
+            "---\ntitle: \"Teams: the #1 \\\"API\\\"\"\nlink: \"[docs](https://example.com)\"\n---\n\n",
+            to_front_matter(&[
+                ("title".to_string(), "Teams: the #1 \"API\"".to_string()),
+                ("link".to_string(), "[docs](https://example.com)".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_to_list_item_should_create_item_when_top_level() {
+        assert_eq!("* a\n", to_list_item(0, "a"));
+    }
+
+    #[test]
+    fn test_to_list_item_should_indent_item_when_nested() {
+        assert_eq!("    * a\n", to_list_item(2, "a"));
+    }
+
+    #[test]
+    fn test_to_link_should_create_link() {
+        assert_eq!("[text](#target)", to_link("text", "#target"));
+    }
+
+    #[test]
+    fn test_to_anchor_should_create_anchor() {
+        assert_eq!("<a id=\"Player\"></a>\n\n", to_anchor("Player"));
+    }
+
     #[test]
     fn test_to_notice_should_create_notice() {
         assert_eq!("_My notice_\n", to_notice("My notice"));
@@ -1,12 +1,24 @@
-use reqwest::blocking::Client;
+use super::http::{GraphQLRequest, HttpClient, HttpOptions};
+use super::introspection::{self, Capabilities, IntrospectionError};
+use super::sdl;
+use flate2::read::GzDecoder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::{boxed::Box, error::Error, fmt, fs, path::PathBuf};
-
-const TYPE_LEVELS: u32 = 7;
+use std::{
+    boxed::Box,
+    collections::HashMap,
+    error::Error,
+    fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SCHEMA_EXTENSIONS: [&str; 3] = ["graphql", "graphqls", "gql"];
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
 
 #[derive(Debug)]
-struct SchemaError {
+pub struct SchemaError {
     message: String,
 }
 
@@ -27,94 +39,155 @@ impl fmt::Display for SchemaError {
 impl Error for SchemaError {}
 
 pub trait TableItem {
-    fn table_fields(&self) -> Vec<String>;
+    fn name(&self) -> Option<&str>;
+
+    // Formats the item as table cells, using link to render each named type.
+    fn table_fields(&self, link: &dyn Fn(&str) -> String) -> Vec<String>;
 }
 
-fn to_safe_string(opt_s: &Option<String>) -> String {
+pub fn to_safe_string(opt_s: &Option<String>) -> String {
     match opt_s {
         Some(s) => s.trim().replace("\n", ""),
         None => "".to_string(),
     }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+// Strikes through the name of a deprecated item and adds the deprecation
+// reason to its description.
+fn deprecated_cells(
+    name: &Option<String>,
+    description: &Option<String>,
+    is_deprecated: Option<bool>,
+    deprecation_reason: &Option<String>,
+) -> (String, String) {
+    let name = to_safe_string(name);
+    let description = to_safe_string(description);
+    if is_deprecated != Some(true) {
+        return (name, description);
+    }
+
+    let notice = match deprecation_reason {
+        Some(_) => format!("**Deprecated:** {}", to_safe_string(deprecation_reason)),
+        None => "**Deprecated**".to_string(),
+    };
+    let description = if description.is_empty() {
+        notice
+    } else {
+        format!("{} {}", description, notice)
+    };
+    (format!("~~{}~~", name), description)
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Type {
     pub name: Option<String>,
     pub kind: Option<String>,
     pub description: Option<String>,
     pub fields: Option<Vec<Field>>,
-    #[serde(alias = "inputFields")]
+    #[serde(rename(serialize = "inputFields"), alias = "inputFields")]
     pub inputs: Option<Vec<Input>>,
     pub interfaces: Option<Vec<TypeRef>>,
-    #[serde(alias = "enumValues")]
+    #[serde(rename(serialize = "enumValues"), alias = "enumValues")]
     pub enums: Option<Vec<Enum>>,
-    #[serde(alias = "possibleTypes")]
+    #[serde(rename(serialize = "possibleTypes"), alias = "possibleTypes")]
     pub possible_types: Option<Vec<TypeRef>>,
+    #[serde(
+        rename(serialize = "specifiedByURL"),
+        alias = "specifiedByURL",
+        alias = "specifiedByUrl"
+    )]
+    pub specified_by_url: Option<String>,
+    #[serde(rename(serialize = "isOneOf"), alias = "isOneOf")]
+    pub is_one_of: Option<bool>,
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Field {
     pub name: Option<String>,
     pub description: Option<String>,
     pub args: Option<Vec<Input>>,
-    #[serde(alias = "type")]
+    #[serde(rename(serialize = "type"), alias = "type")]
     pub field_type: Option<TypeRef>,
-    #[serde(alias = "isDeprecated")]
+    #[serde(rename(serialize = "isDeprecated"), alias = "isDeprecated")]
     pub is_deprecated: Option<bool>,
-    #[serde(alias = "deprecationReason")]
+    #[serde(rename(serialize = "deprecationReason"), alias = "deprecationReason")]
     pub deprecation_reason: Option<String>,
 }
 
 impl TableItem for Field {
-    fn table_fields(&self) -> Vec<String> {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn table_fields(&self, link: &dyn Fn(&str) -> String) -> Vec<String> {
         let type_name = match self.field_type.as_ref() {
-            Some(typ) => typ.decorated_name(),
+            Some(typ) => typ.linked_name(link),
             None => "".to_string(),
         };
-        vec![
-            to_safe_string(&self.name),
-            type_name,
-            to_safe_string(&self.description),
-        ]
+        let (name, description) = deprecated_cells(
+            &self.name,
+            &self.description,
+            self.is_deprecated,
+            &self.deprecation_reason,
+        );
+        vec![name, type_name, description]
     }
 }
 
-#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Input {
     pub name: Option<String>,
     pub description: Option<String>,
-    #[serde(alias = "type")]
+    #[serde(rename(serialize = "type"), alias = "type")]
     pub input_type: Option<TypeRef>,
-    #[serde(alias = "defaultValue")]
+    #[serde(rename(serialize = "defaultValue"), alias = "defaultValue")]
     pub default_value: Option<String>,
+    #[serde(rename(serialize = "isDeprecated"), alias = "isDeprecated")]
+    pub is_deprecated: Option<bool>,
+    #[serde(rename(serialize = "deprecationReason"), alias = "deprecationReason")]
+    pub deprecation_reason: Option<String>,
 }
 
 impl TableItem for Input {
-    fn table_fields(&self) -> Vec<String> {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn table_fields(&self, link: &dyn Fn(&str) -> String) -> Vec<String> {
         let type_name = match self.input_type.as_ref() {
-            Some(typ) => typ.decorated_name(),
+            Some(typ) => typ.linked_name(link),
             None => "".to_string(),
         };
+        let (name, description) = deprecated_cells(
+            &self.name,
+            &self.description,
+            self.is_deprecated,
+            &self.deprecation_reason,
+        );
         vec![
-            to_safe_string(&self.name),
+            name,
             type_name,
-            to_safe_string(&self.description),
+            description,
             to_safe_string(&self.default_value),
         ]
     }
 }
-#[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, Ord, PartialEq, PartialOrd, Serialize)]
 pub struct Enum {
     pub name: Option<String>,
     pub description: Option<String>,
-    #[serde(alias = "isDeprecated")]
+    #[serde(rename(serialize = "isDeprecated"), alias = "isDeprecated")]
     pub is_deprecated: Option<bool>,
-    #[serde(alias = "deprecationReason")]
+    #[serde(rename(serialize = "deprecationReason"), alias = "deprecationReason")]
     pub deprecation_reason: Option<String>,
 }
 
 impl TableItem for Enum {
-    fn table_fields(&self) -> Vec<String> {
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn table_fields(&self, _link: &dyn Fn(&str) -> String) -> Vec<String> {
         let is_deprecated = match &self.is_deprecated {
             Some(is_deprecated) => *is_deprecated,
             None => false,
@@ -137,7 +210,7 @@ impl TableItem for Enum {
 pub struct TypeRef {
     pub name: Option<String>,
     pub kind: Option<String>,
-    #[serde(alias = "ofType")]
+    #[serde(rename(serialize = "ofType"), alias = "ofType")]
     pub of_type: Option<Box<TypeRef>>,
 }
 
@@ -151,89 +224,267 @@ impl TypeRef {
     }
 
     pub fn decorated_name(&self) -> String {
-        self.recurse_decorated_name(TYPE_LEVELS)
-    }
+        self.linked_name(&|name| name.to_string())
+    }
+
+    // Like decorated_name, but renders the named type with link so the list
+    // and non-null decorations stay outside of it.
+    pub fn linked_name(&self, link: &dyn Fn(&str) -> String) -> String {
+        let mut s = match (&self.name, &self.of_type) {
+            (Some(name), _) => link(name),
+            (None, Some(typ)) => typ.linked_name(link),
+            // The introspection query didn't reach the named type.
+            (None, None) if self.is_truncated() => "?".to_string(),
+            (None, None) => "".to_string(),
+        };
 
-    fn recurse_decorated_name(&self, level: u32) -> String {
-        if level == 0 {
-            return "".to_string();
+        if self.is_required() {
+            s.push('!');
         }
 
-        let mut s = String::new();
+        if self.is_list() {
+            s.insert(0, '[');
+            s.push(']');
+        }
 
-        let name = match &self.name {
-            Some(name) => name.clone(),
-            None => match &self.of_type {
-                Some(typ) => typ.recurse_decorated_name(level - 1),
-                None => "".to_string(),
-            },
-        };
+        s
+    }
 
-        s.push_str(&name);
+    // Whether the list and non-null wrappers end before reaching a named type,
+    // which happens when they're nested deeper than the query asked for.
+    pub fn is_truncated(&self) -> bool {
+        match &self.of_type {
+            Some(typ) => typ.is_truncated(),
+            None => self.name.is_none() && (self.is_list() || self.is_required()),
+        }
+    }
 
-        if self.is_required() {
-            s.push_str("!");
+    // The name of the named type inside any list and non-null wrappers.
+    pub fn base_name(&self) -> Option<&str> {
+        match &self.name {
+            Some(name) => Some(name),
+            None => self.of_type.as_ref().and_then(|typ| typ.base_name()),
         }
+    }
+}
 
-        if self.is_list() {
-            s.insert_str(0, "[");
-            s.push_str("]");
+impl Type {
+    // Sorts the fields, their arguments, and the type's other members by name.
+    pub fn normalize(&mut self) {
+        for field in self.fields.iter_mut().flatten() {
+            sort_by_name(&mut field.args, |arg| &arg.name);
+        }
+        sort_by_name(&mut self.fields, |field| &field.name);
+        sort_by_name(&mut self.inputs, |input| &input.name);
+        sort_by_name(&mut self.interfaces, |interface| &interface.name);
+        sort_by_name(&mut self.enums, |value| &value.name);
+        sort_by_name(&mut self.possible_types, |possible| &possible.name);
+    }
+
+    fn replace_truncated(&mut self, resolved: &Type) {
+        for field in self.fields.iter_mut().flatten() {
+            let resolved_field = match resolved
+                .fields
+                .iter()
+                .flatten()
+                .find(|other| other.name == field.name)
+            {
+                Some(resolved_field) => resolved_field,
+                None => continue,
+            };
+            replace_truncated(&mut field.field_type, &resolved_field.field_type);
+            replace_truncated_inputs(&mut field.args, &resolved_field.args);
         }
 
-        s
+        replace_truncated_inputs(&mut self.inputs, &resolved.inputs);
+    }
+}
+
+fn replace_truncated(typ: &mut Option<TypeRef>, resolved: &Option<TypeRef>) {
+    if typ.as_ref().is_some_and(TypeRef::is_truncated) && resolved.is_some() {
+        *typ = resolved.clone();
+    }
+}
+
+fn replace_truncated_inputs(inputs: &mut Option<Vec<Input>>, resolved: &Option<Vec<Input>>) {
+    for input in inputs.iter_mut().flatten() {
+        if let Some(resolved_input) = resolved
+            .iter()
+            .flatten()
+            .find(|other| other.name == input.name)
+        {
+            replace_truncated(&mut input.input_type, &resolved_input.input_type);
+        }
     }
 }
 
+// A place in the schema that refers to a type.
+#[derive(Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Reference {
+    Field {
+        type_name: String,
+        field_name: String,
+    },
+    Argument {
+        type_name: String,
+        field_name: String,
+        argument_name: String,
+    },
+    InputField {
+        type_name: String,
+        field_name: String,
+    },
+    Interface {
+        type_name: String,
+    },
+    UnionMember {
+        type_name: String,
+    },
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Directive {
-    name: Option<String>,
-    description: Option<String>,
-    locations: Option<Vec<String>>,
-    args: Option<Vec<Input>>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub locations: Option<Vec<String>>,
+    pub args: Option<Vec<Input>>,
+    #[serde(rename(serialize = "isRepeatable"), alias = "isRepeatable")]
+    pub is_repeatable: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct Schema {
-    #[serde(alias = "queryType")]
+    pub description: Option<String>,
+    #[serde(rename(serialize = "queryType"), alias = "queryType")]
     pub query_type: Option<Type>,
-    #[serde(alias = "mutationType")]
+    #[serde(rename(serialize = "mutationType"), alias = "mutationType")]
     pub mutation_type: Option<Type>,
-    #[serde(alias = "subscriptionType")]
+    #[serde(rename(serialize = "subscriptionType"), alias = "subscriptionType")]
     pub subscription_type: Option<Type>,
     pub types: Option<Vec<Type>>,
     pub directives: Option<Vec<Directive>>,
 }
 
 impl Schema {
-    pub fn from_url(url: &str, headers: &[String]) -> Result<Schema, Box<dyn Error>> {
-        let client = Client::new();
-        let mut post = client.post(url);
-        for header in headers {
-            let split: Vec<&str> = header.split(':').collect();
-            if split.len() == 2 {
-                post = post.header(split[0], split[1]);
-            }
+    // Introspects the schema at the URL. A custom query is sent as is, on
+    // its own; otherwise the query is built from the capabilities the server
+    // reports, and types nested too deeply for it are fetched separately.
+    pub fn from_url(
+        url: &str,
+        options: &HttpOptions,
+        query: Option<&GraphQLRequest>,
+    ) -> Result<Schema, Box<dyn Error>> {
+        let client = HttpClient::new(options)?;
+        if let Some(query) = query {
+            return Schema::from_str(&client.execute(url, query)?);
         }
-        let text = post
-            .header("Content-Type", "application/json")
-            .body(format!("{{\"query\": \"{}\"}}", SCHEMA_QUERY).replace("\n", ""))
-            .send()?
-            .text()?;
 
-        Schema::from_str(&text)
+        // Servers that reject the probe get the query without any of the
+        // optional fields.
+        let capabilities = match client
+            .query(url, introspection::CAPABILITIES_QUERY)
+            .and_then(|text| Capabilities::from_str(&text))
+        {
+            Ok(capabilities) => capabilities,
+            Err(err)
+                if err
+                    .downcast_ref::<IntrospectionError>()
+                    .is_some_and(IntrospectionError::is_validation_error) =>
+            {
+                eprintln!(
+                    "warning: the server rejected the capabilities query, so optional introspection fields are left out: {}",
+                    err
+                );
+                Capabilities::default()
+            }
+            Err(err) => return Err(err),
+        };
+        let text = client.query(url, &introspection::schema_query(&capabilities))?;
+
+        let mut schema = Schema::from_str(&text)?;
+        schema.resolve_truncated(
+            &mut |name| {
+                let query = introspection::type_query(name, &capabilities);
+                match client.query(url, &query) {
+                    Ok(text) => Some(text),
+                    Err(err) => {
+                        eprintln!("warning: couldn't fetch the types in {}: {}", name, err);
+                        None
+                    }
+                }
+            },
+            &mut || {
+                let query = introspection::directives_query(&capabilities);
+                match client.query(url, &query) {
+                    Ok(text) => Some(text),
+                    Err(err) => {
+                        eprintln!("warning: couldn't fetch the types in directives: {}", err);
+                        None
+                    }
+                }
+            },
+        );
+        Ok(schema)
     }
 
-    pub fn from_json(file: &PathBuf) -> Result<Schema, Box<dyn Error>> {
-        let contents = fs::read_to_string(file)?;
-        Schema::from_str(&contents)
+    // Reads an introspection result from a file, or from stdin when the file
+    // is -. Gzip-compressed input is decompressed.
+    pub fn from_json(file: &Path) -> Result<Schema, Box<dyn Error>> {
+        let name = if file == Path::new("-") {
+            "stdin".to_string()
+        } else {
+            file.display().to_string()
+        };
+        let located = |err: &dyn Error| SchemaError::new(&format!("{}: {}", name, err));
+
+        let mut bytes = Vec::new();
+        if file == Path::new("-") {
+            io::stdin()
+                .read_to_end(&mut bytes)
+                .map_err(|err| located(&err))?;
+        } else {
+            bytes = fs::read(file).map_err(|err| located(&err))?;
+        }
+
+        let mut contents = String::new();
+        if bytes.starts_with(&GZIP_MAGIC) {
+            GzDecoder::new(&bytes[..])
+                .read_to_string(&mut contents)
+                .map_err(|err| located(&err))?;
+        } else {
+            contents = String::from_utf8(bytes).map_err(|err| located(&err))?;
+        }
+        Ok(Schema::from_str(&contents).map_err(|err| located(err.as_ref()))?)
     }
 
-    pub fn from_schema(_file: &PathBuf) -> Result<Schema, Box<dyn Error>> {
-        Err(Box::new(SchemaError::new("not yet implemented")))
+    pub fn from_schema(paths: &[PathBuf]) -> Result<Schema, Box<dyn Error>> {
+        let mut files = Vec::new();
+        for file in schema_files(paths)? {
+            let contents = fs::read_to_string(&file)
+                .map_err(|err| SchemaError::new(&format!("{}: {}", file.display(), err)))?;
+            files.push((file, contents));
+        }
+        Ok(sdl::parse_files(&files)?)
     }
 
     pub fn from_str(text: &str) -> Result<Schema, Box<dyn Error>> {
-        match serde_json::from_str(&text)? {
+        match serde_json::from_str(text)? {
+            // Servers answer with errors and no schema when introspection is
+            // disabled or the request isn't authorized.
+            Value::Object(map)
+                if map.contains_key("errors")
+                    && map
+                        .get("data")
+                        .and_then(|data| data.get("__schema"))
+                        .is_none_or(Value::is_null) =>
+            {
+                Err(Box::new(IntrospectionError::from_response(None, text)))
+            }
+            // Saved schemas, such as the output of graphql-js's
+            // introspectionFromSchema, often leave off the data envelope.
+            Value::Object(map) if map.contains_key("__schema") => {
+                Ok(serde_json::from_value(map["__schema"].clone())?)
+            }
             Value::Object(map) => match map.get("data") {
                 Some(data) => match data.get("__schema") {
                     Some(schema) => {
@@ -254,6 +505,38 @@ impl Schema {
         }
     }
 
+    // Sorts the types, directives, and everything in them by name, so the
+    // same schema always serializes the same way.
+    pub fn normalize(&mut self) {
+        for typ in self.types.iter_mut().flatten() {
+            typ.normalize();
+        }
+        sort_by_name(&mut self.types, |typ| &typ.name);
+        for directive in self.directives.iter_mut().flatten() {
+            sort_by_name(&mut directive.args, |arg| &arg.name);
+        }
+        sort_by_name(&mut self.directives, |directive| &directive.name);
+    }
+
+    // Serializes the schema as a pretty-printed introspection response, with
+    // where and when it was fetched under extensions.
+    pub fn to_introspection_json(
+        &self,
+        source: &str,
+        fetched_at: SystemTime,
+    ) -> Result<String, Box<dyn Error>> {
+        let response = serde_json::json!({
+            "data": { "__schema": self },
+            "extensions": {
+                "gumwood": {
+                    "source": source,
+                    "fetchedAt": to_timestamp(fetched_at),
+                }
+            }
+        });
+        Ok(serde_json::to_string_pretty(&response)? + "\n")
+    }
+
     pub fn get_query_name(&self) -> Option<String> {
         Schema::get_type_name(&self.query_type)
     }
@@ -307,115 +590,356 @@ impl Schema {
         vec
     }
 
-    fn get_type_name(typ: &Option<Type>) -> Option<String> {
-        typ.as_ref().and_then(|typ| typ.name.clone())
-    }
-}
+    // Indexes, by type name, every field, argument, input field, interface
+    // implementation, and union membership that refers to that type.
+    pub fn get_references(&self) -> HashMap<String, Vec<Reference>> {
+        let mut references: HashMap<String, Vec<Reference>> = HashMap::new();
+        let mut add = |typ: Option<&TypeRef>, reference: Reference| {
+            if let Some(name) = typ.and_then(|typ| typ.base_name()) {
+                references
+                    .entry(name.to_string())
+                    .or_default()
+                    .push(reference);
+            }
+        };
+
+        for typ in self.types.iter().flatten() {
+            let type_name = match &typ.name {
+                Some(name) => name.clone(),
+                None => continue,
+            };
+
+            for field in typ.fields.iter().flatten() {
+                let field_name = field.name.clone().unwrap_or_default();
+                add(
+                    field.field_type.as_ref(),
+                    Reference::Field {
+                        type_name: type_name.clone(),
+                        field_name: field_name.clone(),
+                    },
+                );
+                for arg in field.args.iter().flatten() {
+                    add(
+                        arg.input_type.as_ref(),
+                        Reference::Argument {
+                            type_name: type_name.clone(),
+                            field_name: field_name.clone(),
+                            argument_name: arg.name.clone().unwrap_or_default(),
+                        },
+                    );
+                }
+            }
+
+            for input in typ.inputs.iter().flatten() {
+                add(
+                    input.input_type.as_ref(),
+                    Reference::InputField {
+                        type_name: type_name.clone(),
+                        field_name: input.name.clone().unwrap_or_default(),
+                    },
+                );
+            }
+
+            for interface in typ.interfaces.iter().flatten() {
+                add(
+                    Some(interface),
+                    Reference::Interface {
+                        type_name: type_name.clone(),
+                    },
+                );
+            }
+
+            if typ.kind.as_deref() == Some("UNION") {
+                for member in typ.possible_types.iter().flatten() {
+                    add(
+                        Some(member),
+                        Reference::UnionMember {
+                            type_name: type_name.clone(),
+                        },
+                    );
+                }
+            }
+        }
 
-const SCHEMA_QUERY: &str = r#"query IntrospectionQuery {
-  __schema {
-    queryType {
-      name
+        for list in references.values_mut() {
+            list.sort();
+            list.dedup();
+        }
+
+        references
     }
-    mutationType {
-      name
+
+    // Lists each field, argument, and input field whose type is truncated,
+    // as Type.field, Type.field(argument), or @directive(argument).
+    pub fn get_truncated(&self) -> Vec<String> {
+        let mut truncated = Vec::new();
+        let is_truncated = |typ: &Option<TypeRef>| typ.as_ref().is_some_and(TypeRef::is_truncated);
+
+        for typ in self.types.iter().flatten() {
+            let type_name = typ.name.as_deref().unwrap_or_default();
+            for field in typ.fields.iter().flatten() {
+                let field_name = field.name.as_deref().unwrap_or_default();
+                if is_truncated(&field.field_type) {
+                    truncated.push(format!("{}.{}", type_name, field_name));
+                }
+                for arg in field.args.iter().flatten() {
+                    if is_truncated(&arg.input_type) {
+                        truncated.push(format!(
+                            "{}.{}({})",
+                            type_name,
+                            field_name,
+                            arg.name.as_deref().unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+            for input in typ.inputs.iter().flatten() {
+                if is_truncated(&input.input_type) {
+                    truncated.push(format!(
+                        "{}.{}",
+                        type_name,
+                        input.name.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+
+        for directive in self.directives.iter().flatten() {
+            for arg in directive.args.iter().flatten() {
+                if is_truncated(&arg.input_type) {
+                    truncated.push(format!(
+                        "@{}({})",
+                        directive.name.as_deref().unwrap_or_default(),
+                        arg.name.as_deref().unwrap_or_default()
+                    ));
+                }
+            }
+        }
+
+        truncated
     }
-    subscriptionType {
-      name
+
+    // Replaces truncated types with the ones from deeper queries. fetch_type
+    // returns the response to a type_query for the named type, and
+    // fetch_directives the response to a directives_query; types they fail to
+    // fetch stay truncated.
+    pub fn resolve_truncated(
+        &mut self,
+        fetch_type: &mut dyn FnMut(&str) -> Option<String>,
+        fetch_directives: &mut dyn FnMut() -> Option<String>,
+    ) {
+        let is_truncated = |typ: &Option<TypeRef>| typ.as_ref().is_some_and(TypeRef::is_truncated);
+        let has_truncated_input = |inputs: &Option<Vec<Input>>| {
+            inputs
+                .iter()
+                .flatten()
+                .any(|input| is_truncated(&input.input_type))
+        };
+
+        for typ in self.types.iter_mut().flatten() {
+            let has_truncated =
+                typ.fields.iter().flatten().any(|field| {
+                    is_truncated(&field.field_type) || has_truncated_input(&field.args)
+                }) || has_truncated_input(&typ.inputs);
+            if !has_truncated {
+                continue;
+            }
+
+            let resolved = typ
+                .name
+                .as_deref()
+                .and_then(&mut *fetch_type)
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                .and_then(|response| {
+                    response
+                        .get("data")
+                        .and_then(|data| data.get("__type"))
+                        .and_then(|typ| serde_json::from_value::<Type>(typ.clone()).ok())
+                });
+            if let Some(resolved) = resolved {
+                typ.replace_truncated(&resolved);
+            }
+        }
+
+        let has_truncated = self
+            .directives
+            .iter()
+            .flatten()
+            .any(|directive| has_truncated_input(&directive.args));
+        if !has_truncated {
+            return;
+        }
+
+        let resolved = fetch_directives()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .and_then(|response| {
+                response
+                    .get("data")
+                    .and_then(|data| data.get("__schema"))
+                    .and_then(|schema| schema.get("directives"))
+                    .and_then(|directives| {
+                        serde_json::from_value::<Vec<Directive>>(directives.clone()).ok()
+                    })
+            });
+        for directive in self.directives.iter_mut().flatten() {
+            if let Some(resolved_directive) = resolved
+                .iter()
+                .flatten()
+                .find(|other| other.name == directive.name)
+            {
+                replace_truncated_inputs(&mut directive.args, &resolved_directive.args);
+            }
+        }
     }
-    types {
-      ...FullType
+
+    fn get_type_name(typ: &Option<Type>) -> Option<String> {
+        typ.as_ref().and_then(|typ| typ.name.clone())
     }
-    directives {
-      name
-      description
-      locations
-      args {
-        ...InputValue
-      }
+}
+
+fn sort_by_name<T>(items: &mut Option<Vec<T>>, name: impl Fn(&T) -> &Option<String>) {
+    if let Some(items) = items {
+        items.sort_by(|a, b| name(a).cmp(name(b)));
     }
-  }
 }
 
-fragment FullType on __Type {
-  kind
-  name
-  description
-  fields(includeDeprecated: true) {
-    name
-    description
-    args {
-      ...InputValue
-    }
-    type {
-      ...TypeRef
-    }
-    isDeprecated
-    deprecationReason
-  }
-  inputFields {
-    ...InputValue
-  }
-  interfaces {
-    ...TypeRef
-  }
-  enumValues(includeDeprecated: true) {
-    name
-    description
-    isDeprecated
-    deprecationReason
-  }
-  possibleTypes {
-    ...TypeRef
-  }
+// Formats a time as an RFC 3339 timestamp in UTC, such as
+// 2020-09-01T12:30:00Z.
+fn to_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+
+    // Converts days since the epoch to a civil date; see
+    // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let day_of_era = z % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+// Expands the paths given to `--schema` into a list of files: directories are
+// searched recursively for schema files, and paths that don't exist are
+// treated as glob patterns.
+fn schema_files(paths: &[PathBuf]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut files: Vec<PathBuf> = Vec::new();
+
+    for path in paths.iter() {
+        let mut found = Vec::new();
+        if path.is_dir() {
+            find_schema_files(path, &mut found)?;
+            if found.is_empty() {
+                return Err(Box::new(SchemaError::new(&format!(
+                    "no schema files found in {}",
+                    path.display()
+                ))));
+            }
+        } else if !path.exists() && is_glob(path) {
+            for entry in glob::glob(&path.to_string_lossy())? {
+                let entry = entry?;
+                if entry.is_dir() {
+                    find_schema_files(&entry, &mut found)?;
+                } else {
+                    found.push(entry);
+                }
+            }
+            if found.is_empty() {
+                return Err(Box::new(SchemaError::new(&format!(
+                    "no schema files match {}",
+                    path.display()
+                ))));
+            }
+            found.sort();
+        } else {
+            found.push(path.clone());
+        }
+
+        for file in found {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+
+    Ok(files)
 }
 
-fragment InputValue on __InputValue {
-  name
-  description
-  type {
-    ...TypeRef
-  }
-  defaultValue
+fn find_schema_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    entries.sort();
+
+    for entry in entries {
+        if entry.is_dir() {
+            find_schema_files(&entry, files)?;
+        } else if entry
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| SCHEMA_EXTENSIONS.contains(&ext))
+        {
+            files.push(entry);
+        }
+    }
+
+    Ok(())
 }
 
-fragment TypeRef on __Type {
-  kind
-  name
-  ofType {
-    kind
-    name
-    ofType {
-      kind
-      name
-      ofType {
-        kind
-        name
-        ofType {
-          kind
-          name
-          ofType {
-            kind
-            name
-            ofType {
-              kind
-              name
-              ofType {
-                kind
-                name
-              }
-            }
-          }
-        }
-      }
-    }
-  }
-}"#;
+fn is_glob(path: &Path) -> bool {
+    path.to_string_lossy().contains(['*', '?', '['])
+}
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
 
+    // A directory for one test's files, named for the process and the test so
+    // concurrent runs don't collide, and removed when dropped.
+    pub struct TempDir {
+        path: PathBuf,
+    }
+
+    impl TempDir {
+        pub fn new(test: &str) -> TempDir {
+            let path =
+                std::env::temp_dir().join(format!("gumwood-{}-{}", std::process::id(), test));
+            fs::create_dir_all(&path).unwrap();
+            TempDir { path }
+        }
+
+        pub fn join(&self, name: &str) -> PathBuf {
+            self.path.join(name)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.path);
+        }
+    }
+
     #[test]
     fn test_should_pass_when_empty_schema() {
         let response = r#"{
@@ -449,6 +973,216 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_should_report_errors_when_introspection_disabled() {
+        let response = r#"{
+            "data": null,
+            "errors": [
+                {
+                    "message": "GraphQL introspection is not allowed",
+                    "locations": [{ "line": 2, "column": 3 }],
+                    "extensions": { "code": "GRAPHQL_VALIDATION_FAILED" }
+                }
+            ]
+        }"#;
+        match Schema::from_str(response) {
+            Ok(_) => panic!("schema should have errors"),
+            Err(err) => assert_eq!(
+                "introspection failed: GraphQL introspection is not allowed (line 2, column 3) [GRAPHQL_VALIDATION_FAILED]",
+                err.to_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn test_from_url_should_report_http_status_and_errors() {
+        let body = r#"{"errors": [{"message": "Invalid token", "extensions": {"code": "UNAUTHENTICATED"}}]}"#;
+        let url = crate::http::tests::serve(vec![("401 Unauthorized", body)]);
+        match Schema::from_url(&url, &HttpOptions::default(), None) {
+            Ok(_) => panic!("schema should not load"),
+            Err(err) => assert_eq!(
+                "introspection failed with HTTP status 401 Unauthorized: Invalid token [UNAUTHENTICATED]",
+                err.to_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn test_from_url_should_fall_back_when_capabilities_query_rejected(
+    ) -> Result<(), Box<dyn Error>> {
+        let (url, requests) = crate::http::tests::serve_recording(vec![
+            (
+                "400 Bad Request",
+                r#"{"errors": [{"message": "Unknown type \"__Directive\"", "extensions": {"code": "GRAPHQL_VALIDATION_FAILED"}}]}"#,
+            ),
+            (
+                "200 OK",
+                r#"{"data": {"__schema": {"queryType": {"name": "Query"}}}}"#,
+            ),
+        ]);
+        let schema = Schema::from_url(&url, &HttpOptions::default(), None)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        assert!(requests.recv()?.contains("CapabilitiesQuery"));
+        assert!(!requests.recv()?.contains("isRepeatable"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_url_should_report_capabilities_query_http_error() {
+        let url = crate::http::tests::serve(vec![("403 Forbidden", "<html>Forbidden</html>")]);
+        match Schema::from_url(&url, &HttpOptions::default(), None) {
+            Ok(_) => panic!("schema should not load"),
+            Err(err) => assert_eq!(
+                "introspection failed with HTTP status 403 Forbidden",
+                err.to_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn test_from_url_should_send_only_custom_query() -> Result<(), Box<dyn Error>> {
+        let (url, requests) = crate::http::tests::serve_recording(vec![(
+            "200 OK",
+            r#"{"data": {"__schema": {"queryType": {"name": "Query"}}}}"#,
+        )]);
+        let query = GraphQLRequest::new("query Docs { __schema { queryType { name } } }");
+        let schema = Schema::from_url(&url, &HttpOptions::default(), Some(&query))?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        assert!(requests.recv()?.contains(r#""operationName":"Docs""#));
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_timestamp() {
+        let cases = [
+            (0, "1970-01-01T00:00:00Z"),
+            (951_782_400, "2000-02-29T00:00:00Z"),
+            (1_598_963_400, "2020-09-01T12:30:00Z"),
+            (4_107_542_399, "2100-02-28T23:59:59Z"),
+        ];
+        for (seconds, timestamp) in cases.iter() {
+            let time = UNIX_EPOCH + std::time::Duration::from_secs(*seconds);
+            assert_eq!(*timestamp, to_timestamp(time));
+        }
+    }
+
+    #[test]
+    fn test_introspection_json_should_round_trip() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")])?;
+        let expected = crate::schema_markdown::Markdown::with_front_matter(None)?
+            .generate_single_from_schema(&schema, "schema");
+
+        schema.normalize();
+        let json = schema.to_introspection_json("https://example.com/graphql", UNIX_EPOCH)?;
+        let response: Value = serde_json::from_str(&json)?;
+        assert_eq!(
+            serde_json::json!({"source": "https://example.com/graphql", "fetchedAt": "1970-01-01T00:00:00Z"}),
+            response["extensions"]["gumwood"]
+        );
+        assert!(response["data"]["__schema"]["queryType"]["name"].is_string());
+
+        let loaded = Schema::from_str(&json)?;
+        assert_eq!(
+            expected,
+            crate::schema_markdown::Markdown::with_front_matter(None)?
+                .generate_single_from_schema(&loaded, "schema")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_should_sort_by_name() -> Result<(), Box<dyn Error>> {
+        let mut schema = Schema::from_str(
+            r#"{"__schema": {
+                "types": [
+                    {"name": "B", "fields": [{"name": "y", "args": [{"name": "q"}, {"name": "p"}]}, {"name": "x"}]},
+                    {"name": "A", "enumValues": [{"name": "TWO"}, {"name": "ONE"}]}
+                ],
+                "directives": [{"name": "z"}, {"name": "a"}]
+            }}"#,
+        )?;
+        schema.normalize();
+        let json = serde_json::to_value(&schema)?;
+        assert_eq!("A", json["types"][0]["name"]);
+        assert_eq!("ONE", json["types"][0]["enumValues"][0]["name"]);
+        assert_eq!("x", json["types"][1]["fields"][0]["name"]);
+        assert_eq!("p", json["types"][1]["fields"][1]["args"][0]["name"]);
+        assert_eq!("a", json["directives"][0]["name"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_should_accept_bare_schema() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_str(r#"{"__schema": {"queryType": {"name": "Query"}}}"#)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_should_accept_data_with_errors() -> Result<(), Box<dyn Error>> {
+        let response = r#"{
+            "data": {"__schema": {"queryType": {"name": "Query"}}},
+            "errors": [{"message": "Field \"legacy\" is deprecated"}]
+        }"#;
+        let schema = Schema::from_str(response)?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_json_should_decompress_gzip() -> Result<(), Box<dyn Error>> {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let dir = TempDir::new("from_json_should_decompress_gzip");
+        let file = dir.join("schema.json.gz");
+        let mut encoder = GzEncoder::new(fs::File::create(&file)?, Compression::default());
+        encoder.write_all(br#"{"data": {"__schema": {"queryType": {"name": "Root"}}}}"#)?;
+        encoder.finish()?;
+
+        let schema = Schema::from_json(&file)?;
+        assert_eq!("Root", schema.get_query_name().unwrap());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_json_should_report_path_when_missing() {
+        match Schema::from_json(Path::new("testdata/missing.json")) {
+            Ok(_) => panic!("schema should not load"),
+            Err(err) => assert!(err.to_string().starts_with("testdata/missing.json: ")),
+        }
+    }
+
+    #[test]
+    fn test_from_json_should_report_path_when_invalid() {
+        let dir = TempDir::new("from_json_should_report_path_when_invalid");
+        let file = dir.join("invalid.json");
+        fs::write(&file, "{\"data\": ").unwrap();
+        match Schema::from_json(&file) {
+            Ok(_) => panic!("invalid json should fail"),
+            Err(err) => assert_eq!(
+                format!(
+                    "{}: EOF while parsing a value at line 1 column 9",
+                    file.display()
+                ),
+                err.to_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn test_from_json_should_report_path_when_not_gzip() {
+        let dir = TempDir::new("from_json_should_report_path_when_not_gzip");
+        let file = dir.join("truncated.json.gz");
+        fs::write(&file, GZIP_MAGIC).unwrap();
+        match Schema::from_json(&file) {
+            Ok(_) => panic!("truncated gzip should fail"),
+            Err(err) => assert!(err
+                .to_string()
+                .starts_with(&format!("{}: ", file.display()))),
+        }
+    }
+
     #[test]
     fn test_should_fail_when_no_schema() {
         let response = r#"{
@@ -798,6 +1532,197 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_should_read_current_introspection_fields() -> Result<(), Box<dyn Error>> {
+        let response = r#"{
+            "data": {
+                "__schema": {
+                    "description": "The league API",
+                    "types": [
+                        {
+                            "kind": "SCALAR",
+                            "name": "UUID",
+                            "specifiedByURL": "https://tools.ietf.org/html/rfc4122"
+                        },
+                        {
+                            "kind": "INPUT_OBJECT",
+                            "name": "PlayerBy",
+                            "isOneOf": true,
+                            "inputFields": [
+                                {
+                                    "name": "legacyId",
+                                    "isDeprecated": true,
+                                    "deprecationReason": "Use id"
+                                }
+                            ]
+                        }
+                    ],
+                    "directives": [
+                        {
+                            "name": "tag",
+                            "isRepeatable": true,
+                            "locations": ["OBJECT"],
+                            "args": []
+                        }
+                    ]
+                }
+            }
+        }"#;
+        let schema = Schema::from_str(response)?;
+        assert_eq!("The league API", schema.description.as_ref().unwrap());
+        assert_eq!(
+            "https://tools.ietf.org/html/rfc4122",
+            schema
+                .get_type("UUID")
+                .unwrap()
+                .specified_by_url
+                .as_ref()
+                .unwrap()
+        );
+        let player_by = schema.get_type("PlayerBy").unwrap();
+        assert_eq!(Some(true), player_by.is_one_of);
+        let input = &player_by.inputs.as_ref().unwrap()[0];
+        assert_eq!(Some(true), input.is_deprecated);
+        assert_eq!("Use id", input.deprecation_reason.as_ref().unwrap());
+        assert_eq!(Some(true), schema.directives.unwrap()[0].is_repeatable);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_schema_should_parse_sdl_file() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")])?;
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        assert_eq!("Mutation", schema.get_mutation_name().unwrap());
+        assert!(schema.get_subscription_name().is_none());
+        assert_eq!(4, schema.get_types_of_kind("OBJECT").len());
+        assert_eq!(1, schema.get_types_of_kind("INPUT_OBJECT").len());
+        assert!(schema.get_type("Member").is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_references_should_index_every_reference() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")])?;
+        let references = schema.get_references();
+        assert_eq!(
+            vec![
+                Reference::Field {
+                    type_name: "Player".to_string(),
+                    field_name: "position".to_string(),
+                },
+                Reference::InputField {
+                    type_name: "AddPlayerInput".to_string(),
+                    field_name: "position".to_string(),
+                },
+            ],
+            references["Position"]
+        );
+        assert_eq!(
+            vec![Reference::Argument {
+                type_name: "Mutation".to_string(),
+                field_name: "addPlayer".to_string(),
+                argument_name: "input".to_string(),
+            }],
+            references["AddPlayerInput"]
+        );
+        assert_eq!(
+            vec![
+                Reference::Interface {
+                    type_name: "Player".to_string(),
+                },
+                Reference::Interface {
+                    type_name: "Team".to_string(),
+                },
+            ],
+            references["Node"]
+        );
+        assert_eq!(
+            Some(&Reference::UnionMember {
+                type_name: "Member".to_string(),
+            }),
+            references["Team"].last()
+        );
+        assert!(!references.contains_key("DateTime"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_schema_should_fail_when_file_missing() {
+        assert!(Schema::from_schema(&[PathBuf::from("testdata/missing.graphql")]).is_err());
+    }
+
+    #[test]
+    fn test_from_schema_should_report_path_when_invalid() {
+        let dir = TempDir::new("from_schema_should_report_path_when_invalid");
+        let file = dir.join("invalid.graphql");
+        fs::write(&file, "type Query {\n  a Int\n}\n").unwrap();
+        match Schema::from_schema(std::slice::from_ref(&file)) {
+            Ok(_) => panic!("invalid sdl should fail"),
+            Err(err) => assert_eq!(
+                format!(
+                    "expected \":\", found name \"Int\"\n --> {}:2:5\n  |\n2 |   a Int\n  |     ^^^",
+                    file.display()
+                ),
+                err.to_string()
+            ),
+        }
+    }
+
+    #[test]
+    fn test_from_schema_should_load_directory() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_schema(&[PathBuf::from("testdata/split")])?;
+        let query = schema.get_type("Query").unwrap();
+        assert_eq!(3, query.fields.as_ref().unwrap().len());
+        let player = schema.get_type("Player").unwrap();
+        assert_eq!(3, player.fields.as_ref().unwrap().len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_schema_should_load_glob_and_files() -> Result<(), Box<dyn Error>> {
+        let schema = Schema::from_schema(&[
+            PathBuf::from("testdata/split/schema.graphql"),
+            PathBuf::from("testdata/split/*/*.graphql*"),
+        ])?;
+        assert!(schema.get_type("Team").is_some());
+        assert_eq!(
+            3,
+            schema
+                .get_type("Query")
+                .unwrap()
+                .fields
+                .as_ref()
+                .unwrap()
+                .len()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_schema_should_fail_when_glob_matches_nothing() {
+        match Schema::from_schema(&[PathBuf::from("testdata/*.nothing")]) {
+            Ok(_) => panic!("empty glob should fail"),
+            Err(err) => assert_eq!("no schema files match testdata/*.nothing", err.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_schema_files_should_skip_duplicates() -> Result<(), Box<dyn Error>> {
+        let files = schema_files(&[
+            PathBuf::from("testdata/split/schema.graphql"),
+            PathBuf::from("testdata/split"),
+        ])?;
+        assert_eq!(
+            vec![
+                PathBuf::from("testdata/split/schema.graphql"),
+                PathBuf::from("testdata/split/players/players.graphql"),
+                PathBuf::from("testdata/split/teams/teams.graphqls"),
+            ],
+            files
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_typeref_is_required_should_return_false_when_kind_is_none() {
         let tr = TypeRef {
@@ -947,6 +1872,250 @@ mod tests {
         assert_eq!("[myName!]!", tr.decorated_name());
     }
 
+    #[test]
+    fn test_field_table_fields_should_strike_through_when_deprecated() {
+        let field = Field {
+            name: Some("nickname".to_string()),
+            description: Some("The nickname".to_string()),
+            args: None,
+            field_type: None,
+            is_deprecated: Some(true),
+            deprecation_reason: Some("Use `name`".to_string()),
+        };
+        assert_eq!(
+            vec![
+                "~~nickname~~",
+                "",
+                "The nickname **Deprecated:** Use `name`"
+            ],
+            field.table_fields(&|name| name.to_string())
+        );
+    }
+
+    #[test]
+    fn test_input_table_fields_should_mark_deprecated_without_reason() {
+        let input = Input {
+            name: Some("legacy".to_string()),
+            description: None,
+            input_type: None,
+            default_value: None,
+            is_deprecated: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            vec!["~~legacy~~", "", "**Deprecated**", ""],
+            input.table_fields(&|name| name.to_string())
+        );
+    }
+
+    // Wraps a named type in the given wrapper kinds, outermost first.
+    fn wrapped(kinds: &[&str], name: Option<&str>) -> TypeRef {
+        let mut typ = TypeRef {
+            name: name.map(str::to_string),
+            kind: name.map(|_| "SCALAR".to_string()),
+            of_type: None,
+        };
+        for kind in kinds.iter().rev() {
+            typ = TypeRef {
+                name: None,
+                kind: Some(kind.to_string()),
+                of_type: if typ.kind.is_some() {
+                    Some(Box::new(typ))
+                } else {
+                    None
+                },
+            };
+        }
+        typ
+    }
+
+    #[test]
+    fn test_typeref_decorated_name_should_render_any_depth() {
+        let tr = wrapped(
+            &[
+                "NON_NULL", "LIST", "NON_NULL", "LIST", "NON_NULL", "LIST", "NON_NULL", "LIST",
+                "NON_NULL",
+            ],
+            Some("String"),
+        );
+        assert!(!tr.is_truncated());
+        assert_eq!("[[[[String!]!]!]!]!", tr.decorated_name());
+    }
+
+    #[test]
+    fn test_typeref_decorated_name_should_mark_truncated() {
+        let tr = wrapped(&["NON_NULL", "LIST", "NON_NULL"], None);
+        assert!(tr.is_truncated());
+        assert_eq!("[?!]!", tr.decorated_name());
+    }
+
+    #[test]
+    fn test_resolve_truncated_should_replace_truncated_types() -> Result<(), Box<dyn Error>> {
+        let response = r#"{
+            "data": {
+                "__schema": {
+                    "types": [
+                        {
+                            "kind": "OBJECT",
+                            "name": "Matrix",
+                            "fields": [
+                                {
+                                    "name": "rows",
+                                    "args": [
+                                        {
+                                            "name": "at",
+                                            "type": { "kind": "LIST", "name": null, "ofType": null }
+                                        }
+                                    ],
+                                    "type": { "kind": "NON_NULL", "name": null, "ofType": null }
+                                },
+                                {
+                                    "name": "size",
+                                    "args": [],
+                                    "type": { "kind": "SCALAR", "name": "Int", "ofType": null }
+                                }
+                            ]
+                        }
+                    ],
+                    "directives": []
+                }
+            }
+        }"#;
+        let mut schema = Schema::from_str(response)?;
+        assert_eq!(
+            vec!["Matrix.rows".to_string(), "Matrix.rows(at)".to_string()],
+            schema.get_truncated()
+        );
+
+        let mut fetched = Vec::new();
+        schema.resolve_truncated(
+            &mut |name| {
+                fetched.push(name.to_string());
+                Some(
+                    r#"{
+                    "data": {
+                        "__type": {
+                            "name": "Matrix",
+                            "fields": [
+                                {
+                                    "name": "rows",
+                                    "args": [],
+                                    "type": {
+                                        "kind": "NON_NULL",
+                                        "ofType": {
+                                            "kind": "LIST",
+                                            "ofType": { "kind": "SCALAR", "name": "Int" }
+                                        }
+                                    }
+                                }
+                            ]
+                        }
+                    }
+                }"#
+                    .to_string(),
+                )
+            },
+            &mut || panic!("directives should not be fetched"),
+        );
+
+        assert_eq!(vec!["Matrix".to_string()], fetched);
+        assert_eq!(vec!["Matrix.rows(at)".to_string()], schema.get_truncated());
+        let fields = schema.get_type("Matrix").unwrap().fields.as_ref().unwrap();
+        assert_eq!(
+            "[Int]!",
+            fields[0].field_type.as_ref().unwrap().decorated_name()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_truncated_should_replace_truncated_directive_args() -> Result<(), Box<dyn Error>>
+    {
+        let mut schema = Schema::from_str(
+            r#"{"__schema": {
+                "types": [],
+                "directives": [
+                    {
+                        "name": "cached",
+                        "locations": ["FIELD_DEFINITION"],
+                        "args": [
+                            {"name": "keys", "type": {"kind": "NON_NULL", "name": null, "ofType": null}}
+                        ]
+                    }
+                ]
+            }}"#,
+        )?;
+        assert_eq!(vec!["@cached(keys)".to_string()], schema.get_truncated());
+
+        schema.resolve_truncated(
+            &mut |_| panic!("types should not be fetched"),
+            &mut || {
+                Some(
+                    r#"{
+                        "data": {
+                            "__schema": {
+                                "directives": [
+                                    {
+                                        "name": "cached",
+                                        "args": [
+                                            {
+                                                "name": "keys",
+                                                "type": {
+                                                    "kind": "NON_NULL",
+                                                    "ofType": {
+                                                        "kind": "LIST",
+                                                        "ofType": { "kind": "SCALAR", "name": "String" }
+                                                    }
+                                                }
+                                            }
+                                        ]
+                                    }
+                                ]
+                            }
+                        }
+                    }"#
+                    .to_string(),
+                )
+            },
+        );
+
+        assert!(schema.get_truncated().is_empty());
+        let args = schema.directives.as_ref().unwrap()[0]
+            .args
+            .as_ref()
+            .unwrap();
+        assert_eq!(
+            "[String]!",
+            args[0].input_type.as_ref().unwrap().decorated_name()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_typeref_linked_name_should_keep_decorations_outside_link() {
+        let tr = TypeRef {
+            name: None,
+            kind: Some("NON_NULL".to_string()),
+            of_type: Some(Box::new(TypeRef {
+                kind: Some("LIST".to_string()),
+                name: None,
+                of_type: Some(Box::new(TypeRef {
+                    kind: Some("NON_NULL".to_string()),
+                    name: None,
+                    of_type: Some(Box::new(TypeRef {
+                        kind: Some("OBJECT".to_string()),
+                        name: Some("User".to_string()),
+                        of_type: None,
+                    })),
+                })),
+            })),
+        };
+        assert_eq!(
+            "[[User](#User)!]!",
+            tr.linked_name(&|name| format!("[{}](#{})", name, name))
+        );
+    }
+
     #[test]
     fn test_typeref_decorated_name_should_return_name_with_brackets_when_not_scalar() {
         let tr = TypeRef {
@@ -0,0 +1,724 @@
+use super::markdown::*;
+use super::schema::{Directive, Field, Input, Schema, Type, TypeRef};
+use super::schema_sdl::{BUILT_IN_DIRECTIVES, BUILT_IN_SCALARS};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+// How a change affects clients of the schema. Breaking changes fail queries
+// that used to work; dangerous changes keep them valid but can change what
+// they return or how clients must handle it.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Criticality {
+    Breaking,
+    Dangerous,
+    Safe,
+}
+
+impl fmt::Display for Criticality {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Criticality::Breaking => write!(f, "Breaking"),
+            Criticality::Dangerous => write!(f, "Dangerous"),
+            Criticality::Safe => write!(f, "Safe"),
+        }
+    }
+}
+
+const CRITICALITIES: [Criticality; 3] = [
+    Criticality::Breaking,
+    Criticality::Dangerous,
+    Criticality::Safe,
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub criticality: Criticality,
+    // Where the change is, such as Type, Type.field, Type.field(arg), or
+    // @directive
+    pub path: String,
+    pub message: String,
+}
+
+impl Change {
+    fn new(criticality: Criticality, path: &str, message: &str) -> Change {
+        Change {
+            criticality,
+            path: path.to_string(),
+            message: message.to_string(),
+        }
+    }
+}
+
+// Finds the changes from the old schema to the new one, ordered by
+// criticality and then path. Built-in scalars and directives and the
+// introspection types are left out, so schemas from different sources
+// compare cleanly.
+pub fn diff(old: &Schema, new: &Schema) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    let old_types = by_name(old.types.as_deref(), |typ| &typ.name);
+    let new_types = by_name(new.types.as_deref(), |typ| &typ.name);
+    for name in names(&old_types, &new_types) {
+        if name.starts_with("__") || BUILT_IN_SCALARS.contains(&name) {
+            continue;
+        }
+        match (old_types.get(name), new_types.get(name)) {
+            (Some(_), None) => {
+                changes.push(Change::new(Criticality::Breaking, name, "Type was removed"))
+            }
+            (None, Some(_)) => changes.push(Change::new(Criticality::Safe, name, "Type was added")),
+            (Some(old), Some(new)) => diff_types(old, new, &mut changes),
+            (None, None) => {}
+        }
+    }
+
+    let old_directives = by_name(old.directives.as_deref(), |directive| &directive.name);
+    let new_directives = by_name(new.directives.as_deref(), |directive| &directive.name);
+    for name in names(&old_directives, &new_directives) {
+        if BUILT_IN_DIRECTIVES.contains(&name) {
+            continue;
+        }
+        let path = format!("@{}", name);
+        match (old_directives.get(name), new_directives.get(name)) {
+            (Some(_), None) => changes.push(Change::new(
+                Criticality::Breaking,
+                &path,
+                "Directive was removed",
+            )),
+            (None, Some(_)) => {
+                changes.push(Change::new(Criticality::Safe, &path, "Directive was added"))
+            }
+            (Some(old), Some(new)) => diff_directives(&path, old, new, &mut changes),
+            (None, None) => {}
+        }
+    }
+
+    changes.sort_by(|a, b| {
+        a.criticality
+            .cmp(&b.criticality)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    changes
+}
+
+pub fn changes_to_text(changes: &[Change]) -> String {
+    if changes.is_empty() {
+        return "No changes\n".to_string();
+    }
+
+    let mut s = String::new();
+    for criticality in CRITICALITIES.iter() {
+        let group: Vec<&Change> = changes
+            .iter()
+            .filter(|change| change.criticality == *criticality)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        if !s.is_empty() {
+            s.push('\n');
+        }
+        s.push_str(&format!("{} changes ({}):\n", criticality, group.len()));
+        for change in group {
+            s.push_str(&format!("  {}: {}\n", change.path, change.message));
+        }
+    }
+    s
+}
+
+pub fn changes_to_markdown(changes: &[Change]) -> String {
+    let mut s = to_header(1, "Schema Changes");
+    if changes.is_empty() {
+        s.push_str(&to_notice("No changes"));
+        return s;
+    }
+
+    for criticality in CRITICALITIES.iter() {
+        let group: Vec<&Change> = changes
+            .iter()
+            .filter(|change| change.criticality == *criticality)
+            .collect();
+        if group.is_empty() {
+            continue;
+        }
+        s.push_str(&to_header(2, &criticality.to_string()));
+        s.push_str(&to_table_row(&["Path".to_string(), "Change".to_string()]));
+        s.push_str(&to_table_separator(2));
+        for change in group {
+            s.push_str(&to_table_row(&[
+                format!("`{}`", change.path),
+                change.message.replace('|', "\\|"),
+            ]));
+        }
+        s.push('\n');
+    }
+    s
+}
+
+fn diff_types(old: &Type, new: &Type, changes: &mut Vec<Change>) {
+    let name = old.name.as_deref().unwrap_or_default();
+    let old_kind = old.kind.as_deref().unwrap_or_default();
+    let new_kind = new.kind.as_deref().unwrap_or_default();
+    if old_kind != new_kind {
+        changes.push(Change::new(
+            Criticality::Breaking,
+            name,
+            &format!("Kind changed from {} to {}", old_kind, new_kind),
+        ));
+        return;
+    }
+
+    diff_fields(name, old, new, changes);
+    diff_members(
+        name,
+        "Interface",
+        old.interfaces.as_deref(),
+        new.interfaces.as_deref(),
+        changes,
+    );
+    // An interface's possible types mirror the implementations, which are
+    // reported on the implementing types.
+    if old_kind == "UNION" {
+        diff_members(
+            name,
+            "Member",
+            old.possible_types.as_deref(),
+            new.possible_types.as_deref(),
+            changes,
+        );
+    }
+
+    let old_inputs = by_name(old.inputs.as_deref(), |input| &input.name);
+    let new_inputs = by_name(new.inputs.as_deref(), |input| &input.name);
+    for input_name in names(&old_inputs, &new_inputs) {
+        let path = format!("{}.{}", name, input_name);
+        diff_input_values(
+            &path,
+            "Input field",
+            old_inputs.get(input_name).copied(),
+            new_inputs.get(input_name).copied(),
+            changes,
+        );
+    }
+
+    let old_values = by_name(old.enums.as_deref(), |value| &value.name);
+    let new_values = by_name(new.enums.as_deref(), |value| &value.name);
+    for value in names(&old_values, &new_values) {
+        let path = format!("{}.{}", name, value);
+        match (old_values.get(value), new_values.get(value)) {
+            (Some(_), None) => changes.push(Change::new(
+                Criticality::Breaking,
+                &path,
+                "Enum value was removed",
+            )),
+            // Clients that switch over the enum may not handle the new value.
+            (None, Some(_)) => changes.push(Change::new(
+                Criticality::Dangerous,
+                &path,
+                "Enum value was added",
+            )),
+            _ => {}
+        }
+    }
+}
+
+fn diff_fields(name: &str, old: &Type, new: &Type, changes: &mut Vec<Change>) {
+    let old_fields = by_name(old.fields.as_deref(), |field| &field.name);
+    let new_fields = by_name(new.fields.as_deref(), |field| &field.name);
+    for field_name in names(&old_fields, &new_fields) {
+        let path = format!("{}.{}", name, field_name);
+        match (old_fields.get(field_name), new_fields.get(field_name)) {
+            (Some(_), None) => changes.push(Change::new(
+                Criticality::Breaking,
+                &path,
+                "Field was removed",
+            )),
+            (None, Some(_)) => {
+                changes.push(Change::new(Criticality::Safe, &path, "Field was added"))
+            }
+            (Some(old), Some(new)) => diff_field(&path, old, new, changes),
+            (None, None) => {}
+        }
+    }
+}
+
+fn diff_field(path: &str, old: &Field, new: &Field, changes: &mut Vec<Change>) {
+    if let (Some(old_type), Some(new_type)) = (&old.field_type, &new.field_type) {
+        if old_type != new_type {
+            let criticality = if is_safe_output_change(old_type, new_type) {
+                Criticality::Safe
+            } else {
+                Criticality::Breaking
+            };
+            changes.push(Change::new(
+                criticality,
+                path,
+                &type_changed_message(old_type, new_type),
+            ));
+        }
+    }
+
+    let old_args = by_name(old.args.as_deref(), |arg| &arg.name);
+    let new_args = by_name(new.args.as_deref(), |arg| &arg.name);
+    for arg in names(&old_args, &new_args) {
+        diff_input_values(
+            &format!("{}({})", path, arg),
+            "Argument",
+            old_args.get(arg).copied(),
+            new_args.get(arg).copied(),
+            changes,
+        );
+    }
+}
+
+// Compares arguments or input fields, which clients send rather than
+// receive, so the rules for their types are the reverse of fields'.
+fn diff_input_values(
+    path: &str,
+    label: &str,
+    old: Option<&Input>,
+    new: Option<&Input>,
+    changes: &mut Vec<Change>,
+) {
+    match (old, new) {
+        (Some(_), None) => changes.push(Change::new(
+            Criticality::Breaking,
+            path,
+            &format!("{} was removed", label),
+        )),
+        (None, Some(new)) => {
+            let required = new.input_type.as_ref().is_some_and(TypeRef::is_required)
+                && new.default_value.is_none();
+            if required {
+                changes.push(Change::new(
+                    Criticality::Breaking,
+                    path,
+                    &format!("Required {} was added", label.to_lowercase()),
+                ));
+            } else {
+                changes.push(Change::new(
+                    Criticality::Dangerous,
+                    path,
+                    &format!("Optional {} was added", label.to_lowercase()),
+                ));
+            }
+        }
+        (Some(old), Some(new)) => {
+            if let (Some(old_type), Some(new_type)) = (&old.input_type, &new.input_type) {
+                if old_type != new_type {
+                    let criticality = if is_safe_input_change(old_type, new_type) {
+                        Criticality::Safe
+                    } else {
+                        Criticality::Breaking
+                    };
+                    changes.push(Change::new(
+                        criticality,
+                        path,
+                        &type_changed_message(old_type, new_type),
+                    ));
+                }
+            }
+            // Clients that leave the value out get different behavior.
+            if old.default_value != new.default_value {
+                changes.push(Change::new(
+                    Criticality::Dangerous,
+                    path,
+                    &format!(
+                        "Default value changed from {} to {}",
+                        old.default_value.as_deref().unwrap_or("none"),
+                        new.default_value.as_deref().unwrap_or("none")
+                    ),
+                ));
+            }
+        }
+        (None, None) => {}
+    }
+}
+
+// Compares the interfaces an object implements or the members of a union.
+fn diff_members(
+    name: &str,
+    label: &str,
+    old: Option<&[TypeRef]>,
+    new: Option<&[TypeRef]>,
+    changes: &mut Vec<Change>,
+) {
+    let old = by_name(old, |member| &member.name);
+    let new = by_name(new, |member| &member.name);
+    for member in names(&old, &new) {
+        match (old.get(member), new.get(member)) {
+            (Some(_), None) => changes.push(Change::new(
+                Criticality::Breaking,
+                name,
+                &format!("{} {} was removed", label, member),
+            )),
+            // Fragments spread on the type may not handle the new member.
+            (None, Some(_)) => changes.push(Change::new(
+                Criticality::Dangerous,
+                name,
+                &format!("{} {} was added", label, member),
+            )),
+            _ => {}
+        }
+    }
+}
+
+fn diff_directives(path: &str, old: &Directive, new: &Directive, changes: &mut Vec<Change>) {
+    let old_locations: BTreeSet<&String> = old.locations.iter().flatten().collect();
+    let new_locations: BTreeSet<&String> = new.locations.iter().flatten().collect();
+    for location in old_locations.difference(&new_locations) {
+        changes.push(Change::new(
+            Criticality::Breaking,
+            path,
+            &format!("Location {} was removed", location),
+        ));
+    }
+    for location in new_locations.difference(&old_locations) {
+        changes.push(Change::new(
+            Criticality::Safe,
+            path,
+            &format!("Location {} was added", location),
+        ));
+    }
+
+    let old_args = by_name(old.args.as_deref(), |arg| &arg.name);
+    let new_args = by_name(new.args.as_deref(), |arg| &arg.name);
+    for arg in names(&old_args, &new_args) {
+        diff_input_values(
+            &format!("{}({})", path, arg),
+            "Argument",
+            old_args.get(arg).copied(),
+            new_args.get(arg).copied(),
+            changes,
+        );
+    }
+}
+
+// A field's type can safely become non-null, since clients still get a
+// value of the type they expect.
+fn is_safe_output_change(old: &TypeRef, new: &TypeRef) -> bool {
+    if old.is_list() {
+        (new.is_list() && both_of_types(old, new, is_safe_output_change))
+            || (new.is_required() && of_type(new).is_some_and(|of| is_safe_output_change(old, of)))
+    } else if old.is_required() {
+        new.is_required() && both_of_types(old, new, is_safe_output_change)
+    } else {
+        (!new.is_list() && !new.is_required() && old.name == new.name)
+            || (new.is_required() && of_type(new).is_some_and(|of| is_safe_output_change(old, of)))
+    }
+}
+
+// An argument or input field's type can safely become nullable, since
+// clients that send a value still send one of the right type.
+fn is_safe_input_change(old: &TypeRef, new: &TypeRef) -> bool {
+    if old.is_list() {
+        new.is_list() && both_of_types(old, new, is_safe_input_change)
+    } else if old.is_required() {
+        (new.is_required() && both_of_types(old, new, is_safe_input_change))
+            || (!new.is_required() && of_type(old).is_some_and(|of| is_safe_input_change(of, new)))
+    } else {
+        !new.is_list() && !new.is_required() && old.name == new.name
+    }
+}
+
+fn of_type(type_ref: &TypeRef) -> Option<&TypeRef> {
+    type_ref.of_type.as_deref()
+}
+
+fn both_of_types(old: &TypeRef, new: &TypeRef, safe: fn(&TypeRef, &TypeRef) -> bool) -> bool {
+    match (of_type(old), of_type(new)) {
+        (Some(old), Some(new)) => safe(old, new),
+        _ => false,
+    }
+}
+
+fn type_changed_message(old: &TypeRef, new: &TypeRef) -> String {
+    format!(
+        "Type changed from {} to {}",
+        old.decorated_name(),
+        new.decorated_name()
+    )
+}
+
+fn by_name<T>(items: Option<&[T]>, name: impl Fn(&T) -> &Option<String>) -> BTreeMap<&str, &T> {
+    items
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| name(item).as_deref().map(|name| (name, item)))
+        .collect()
+}
+
+fn names<'a, T>(old: &BTreeMap<&'a str, T>, new: &BTreeMap<&'a str, T>) -> BTreeSet<&'a str> {
+    old.keys().chain(new.keys()).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn parse(text: &str) -> Schema {
+        crate::sdl::parse_files(&[(PathBuf::from("schema.graphql"), text.to_string())]).unwrap()
+    }
+
+    fn diff_sdl(old: &str, new: &str) -> Vec<(Criticality, String, String)> {
+        diff(&parse(old), &parse(new))
+            .into_iter()
+            .map(|change| (change.criticality, change.path, change.message))
+            .collect()
+    }
+
+    fn change(
+        criticality: Criticality,
+        path: &str,
+        message: &str,
+    ) -> (Criticality, String, String) {
+        (criticality, path.to_string(), message.to_string())
+    }
+
+    #[test]
+    fn test_diff_should_find_no_changes_in_same_schema() {
+        let schema = &Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")]).unwrap();
+        assert!(diff(schema, schema).is_empty());
+    }
+
+    #[test]
+    fn test_diff_should_classify_type_changes() {
+        let changes = diff_sdl(
+            "type Query { a: Int }\ntype Old { a: Int }\nunion Thing = Query",
+            "type Query { a: Int }\ntype New { a: Int }\nenum Thing { A }",
+        );
+        assert_eq!(
+            vec![
+                change(Criticality::Breaking, "Old", "Type was removed"),
+                change(
+                    Criticality::Breaking,
+                    "Thing",
+                    "Kind changed from UNION to ENUM"
+                ),
+                change(Criticality::Safe, "New", "Type was added"),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn test_diff_should_classify_field_changes() {
+        let changes = diff_sdl(
+            "type Query { a: Int b: String c: String! d: [Int] e: Int }",
+            "type Query { a: Int! b: Int c: String d: [Int!]! f: Int }",
+        );
+        assert_eq!(
+            vec![
+                change(
+                    Criticality::Breaking,
+                    "Query.b",
+                    "Type changed from String to Int"
+                ),
+                change(
+                    Criticality::Breaking,
+                    "Query.c",
+                    "Type changed from String! to String"
+                ),
+                change(Criticality::Breaking, "Query.e", "Field was removed"),
+                change(
+                    Criticality::Safe,
+                    "Query.a",
+                    "Type changed from Int to Int!"
+                ),
+                change(
+                    Criticality::Safe,
+                    "Query.d",
+                    "Type changed from [Int] to [Int!]!"
+                ),
+                change(Criticality::Safe, "Query.f", "Field was added"),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn test_diff_should_classify_argument_changes() {
+        let changes = diff_sdl(
+            "type Query { a(x: Int!, y: Int = 1, z: Int): Int }",
+            "type Query { a(x: Int, y: Int = 2, w: Int!, v: String): Int }",
+        );
+        assert_eq!(
+            vec![
+                change(
+                    Criticality::Breaking,
+                    "Query.a(w)",
+                    "Required argument was added"
+                ),
+                change(Criticality::Breaking, "Query.a(z)", "Argument was removed"),
+                change(
+                    Criticality::Dangerous,
+                    "Query.a(v)",
+                    "Optional argument was added"
+                ),
+                change(
+                    Criticality::Dangerous,
+                    "Query.a(y)",
+                    "Default value changed from 1 to 2"
+                ),
+                change(
+                    Criticality::Safe,
+                    "Query.a(x)",
+                    "Type changed from Int! to Int"
+                ),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn test_diff_should_classify_input_field_changes() {
+        let changes = diff_sdl(
+            "type Query { a: Int }\ninput Filter { name: String, limit: Int = 10 }",
+            "type Query { a: Int }\ninput Filter { name: String!, limit: Int = 10, team: ID!, city: String }",
+        );
+        assert_eq!(
+            vec![
+                change(
+                    Criticality::Breaking,
+                    "Filter.name",
+                    "Type changed from String to String!"
+                ),
+                change(
+                    Criticality::Breaking,
+                    "Filter.team",
+                    "Required input field was added"
+                ),
+                change(
+                    Criticality::Dangerous,
+                    "Filter.city",
+                    "Optional input field was added"
+                ),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn test_diff_should_classify_enum_union_and_interface_changes() {
+        let changes = diff_sdl(
+            "type Query { a: Int }\nenum Position { GOALIE DEFENSE }\ninterface Node { id: ID }\ninterface Named { id: ID }\ntype Player implements Node { id: ID }\nunion Member = Player",
+            "type Query { a: Int }\nenum Position { GOALIE FORWARD }\ninterface Node { id: ID }\ninterface Named { id: ID }\ntype Player implements Named { id: ID }\nunion Member = Player | Query",
+        );
+        assert_eq!(
+            vec![
+                change(
+                    Criticality::Breaking,
+                    "Player",
+                    "Interface Node was removed"
+                ),
+                change(
+                    Criticality::Breaking,
+                    "Position.DEFENSE",
+                    "Enum value was removed"
+                ),
+                change(Criticality::Dangerous, "Member", "Member Query was added"),
+                change(
+                    Criticality::Dangerous,
+                    "Player",
+                    "Interface Named was added"
+                ),
+                change(
+                    Criticality::Dangerous,
+                    "Position.FORWARD",
+                    "Enum value was added"
+                ),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn test_diff_should_classify_directive_changes() {
+        let changes = diff_sdl(
+            "type Query { a: Int }\ndirective @cache(ttl: Int) on FIELD_DEFINITION | OBJECT\ndirective @old on FIELD",
+            "type Query { a: Int }\ndirective @cache(ttl: Int = 60) on FIELD_DEFINITION | FIELD\ndirective @new on FIELD",
+        );
+        assert_eq!(
+            vec![
+                change(
+                    Criticality::Breaking,
+                    "@cache",
+                    "Location OBJECT was removed"
+                ),
+                change(Criticality::Breaking, "@old", "Directive was removed"),
+                change(
+                    Criticality::Dangerous,
+                    "@cache(ttl)",
+                    "Default value changed from none to 60"
+                ),
+                change(Criticality::Safe, "@cache", "Location FIELD was added"),
+                change(Criticality::Safe, "@new", "Directive was added"),
+            ],
+            changes
+        );
+    }
+
+    #[test]
+    fn test_diff_should_ignore_built_ins() {
+        let introspected = Schema::from_str(
+            r#"{"__schema": {"types": [
+                {"kind": "OBJECT", "name": "Query", "fields": [
+                    {"name": "a", "args": [], "type": {"kind": "SCALAR", "name": "Int"}}
+                ]},
+                {"kind": "SCALAR", "name": "Int"},
+                {"kind": "OBJECT", "name": "__Schema", "fields": []}
+            ], "directives": [{"name": "skip", "locations": ["FIELD"], "args": []}]}}"#,
+        )
+        .unwrap();
+        assert!(diff(&introspected, &parse("type Query { a: Int }")).is_empty());
+    }
+
+    #[test]
+    fn test_changes_to_text_should_group_by_criticality() {
+        let changes = vec![
+            Change::new(Criticality::Breaking, "Query.a", "Field was removed"),
+            Change::new(Criticality::Safe, "Query.b", "Field was added"),
+            Change::new(Criticality::Safe, "Team", "Type was added"),
+        ];
+        assert_eq!(
+            "Breaking changes (1):\n  Query.a: Field was removed\n\nSafe changes (2):\n  Query.b: Field was added\n  Team: Type was added\n",
+            changes_to_text(&changes)
+        );
+        assert_eq!("No changes\n", changes_to_text(&[]));
+    }
+
+    #[test]
+    fn test_changes_to_markdown_should_render_tables() {
+        let changes = vec![
+            Change::new(Criticality::Breaking, "Query.a", "Field was removed"),
+            Change::new(
+                Criticality::Dangerous,
+                "Query.b(x)",
+                "Default value changed from \"a|b\" to none",
+            ),
+        ];
+        assert_eq!(
+            r#"# Schema Changes
+
+## Breaking
+
+| Path | Change |
+| --- | --- |
+| `Query.a` | Field was removed |
+
+## Dangerous
+
+| Path | Change |
+| --- | --- |
+| `Query.b(x)` | Default value changed from "a\|b" to none |
+
+"#,
+            changes_to_markdown(&changes)
+        );
+        assert_eq!(
+            "# Schema Changes\n\n_No changes_\n",
+            changes_to_markdown(&[])
+        );
+    }
+}
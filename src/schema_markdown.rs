@@ -1,265 +1,1513 @@
 use super::markdown::*;
-use super::schema::{Field, Schema, TableItem, Type};
+use super::schema::{
+    to_safe_string, Directive, Enum, Field, Input, Reference, Schema, TableItem, Type,
+};
+use super::schema_sdl::type_to_sdl;
 use std::collections::HashMap;
 use std::error::Error;
+use std::fmt;
+
+// The order in which categories appear in single-file output.
+const CATEGORIES: [&str; 11] = [
+    "queries",
+    "mutations",
+    "subscriptions",
+    "objects",
+    "interfaces",
+    "unions",
+    "enums",
+    "inputs",
+    "scalars",
+    "directives",
+    "deprecations",
+];
+
+const TYPE_CATEGORIES: [(&str, &str, &str); 6] = [
+    ("objects", "Objects", "OBJECT"),
+    ("interfaces", "Interfaces", "INTERFACE"),
+    ("unions", "Unions", "UNION"),
+    ("enums", "Enums", "ENUM"),
+    ("inputs", "Inputs", "INPUT_OBJECT"),
+    ("scalars", "Scalars", "SCALAR"),
+];
+
+// The placeholders that may appear in front matter values, replaced per file.
+const PLACEHOLDERS: [&str; 3] = ["title", "slug", "category"];
+
+#[derive(Debug)]
+pub struct MarkdownError {
+    message: String,
+}
+
+impl MarkdownError {
+    pub fn new(message: &str) -> MarkdownError {
+        MarkdownError {
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for MarkdownError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for MarkdownError {}
 
 #[derive(Debug)]
 pub struct Markdown {
-    front_matter: Option<String>,
+    front_matter: Vec<(String, String)>,
+}
+
+impl Markdown {
+    pub fn with_front_matter(front_matter: Option<String>) -> Result<Markdown, Box<dyn Error>> {
+        let front_matter = match front_matter {
+            Some(front_matter) => parse_front_matter(&front_matter)?,
+            None => Vec::new(),
+        };
+        Ok(Markdown { front_matter })
+    }
+
+    pub fn generate_from_schema(&self, schema: &Schema) -> HashMap<String, String> {
+        let mut contents = self.generate_sections(schema, true);
+
+        // The queries page is the entry point, so it carries the schema
+        // description.
+        if let (Some(description), Some(markdown)) =
+            (&schema.description, contents.get_mut("queries"))
+        {
+            if !markdown.is_empty() {
+                markdown.insert_str(0, &to_description(description));
+            }
+        }
+
+        for (category, markdown) in contents.iter_mut() {
+            if !markdown.is_empty() {
+                let title = category_title(schema, category);
+                markdown.insert_str(0, &self.front_matter_for(&title, category, category));
+            }
+        }
+
+        contents
+    }
+
+    pub fn generate_single_from_schema(&self, schema: &Schema, name: &str) -> String {
+        let contents = self.generate_sections(schema, false);
+        let mut toc = String::new();
+        let mut body = String::new();
+
+        for category in CATEGORIES.iter() {
+            let markdown = &contents[*category];
+            if markdown.is_empty() {
+                continue;
+            }
+
+            toc.push_str(&to_list_item(
+                0,
+                &to_link(&category_title(schema, category), &format!("#{}", category)),
+            ));
+            for (label, anchor) in category_entries(schema, category) {
+                toc.push_str(&to_list_item(1, &to_link(&label, &format!("#{}", anchor))));
+            }
+
+            body.push_str(&to_anchor(category));
+            body.push_str(markdown);
+        }
+
+        if body.is_empty() {
+            return body;
+        }
+
+        let description = match &schema.description {
+            Some(description) => to_description(description),
+            None => String::new(),
+        };
+
+        format!(
+            "{}{}{}{}\n{}",
+            self.front_matter_for("Schema", name, "schema"),
+            description,
+            to_header(1, "Table of Contents"),
+            toc,
+            body
+        )
+    }
+
+    // Generates the markdown for each category, keyed by category name.
+    fn generate_sections(&self, schema: &Schema, multiple: bool) -> HashMap<String, String> {
+        let mut contents: HashMap<String, String> = HashMap::new();
+        let links = Links::new(schema, multiple);
+
+        for category in CATEGORIES.iter() {
+            let markdown = match root_type_name(schema, category) {
+                Some(type_name) => schema_type_to_markdown(schema, type_name, &links),
+                None => match TYPE_CATEGORIES.iter().find(|(name, _, _)| name == category) {
+                    Some((_, title, kind)) => types_to_markdown(schema, title, kind, &links),
+                    None if *category == "directives" => directives_to_markdown(schema, &links),
+                    None if *category == "deprecations" => deprecations_to_markdown(schema, &links),
+                    None => String::new(),
+                },
+            };
+            contents.insert(category.to_string(), markdown);
+        }
+
+        contents
+    }
+
+    // Renders the front matter block for one output file, substituting its
+    // placeholders.
+    fn front_matter_for(&self, title: &str, slug: &str, category: &str) -> String {
+        let items: Vec<(String, String)> = self
+            .front_matter
+            .iter()
+            .map(|(key, value)| {
+                let value = value
+                    .replace("{title}", title)
+                    .replace("{slug}", slug)
+                    .replace("{category}", category);
+                (key.clone(), value)
+            })
+            .collect();
+        to_front_matter(&items)
+    }
+}
+
+// Parses front matter in key:value;key:value format. Values may contain colons
+// and the placeholders in PLACEHOLDERS.
+fn parse_front_matter(front_matter: &str) -> Result<Vec<(String, String)>, MarkdownError> {
+    let mut items: Vec<(String, String)> = Vec::new();
+
+    for item in front_matter.split(';').map(str::trim) {
+        if item.is_empty() {
+            continue;
+        }
+
+        let (key, value) = match item.find(':') {
+            Some(index) => (item[..index].trim(), item[index + 1..].trim()),
+            None => {
+                return Err(MarkdownError::new(&format!(
+                    "invalid front matter \"{}\": expected key:value",
+                    item
+                )))
+            }
+        };
+
+        if key.is_empty() {
+            return Err(MarkdownError::new(&format!(
+                "invalid front matter \"{}\": missing key",
+                item
+            )));
+        }
+        if key.chars().any(char::is_whitespace) {
+            return Err(MarkdownError::new(&format!(
+                "invalid front matter key \"{}\": keys cannot contain whitespace",
+                key
+            )));
+        }
+        if items.iter().any(|(existing, _)| existing == key) {
+            return Err(MarkdownError::new(&format!(
+                "duplicate front matter key \"{}\"",
+                key
+            )));
+        }
+        validate_placeholders(key, value)?;
+
+        items.push((key.to_string(), value.to_string()));
+    }
+
+    Ok(items)
+}
+
+fn validate_placeholders(key: &str, value: &str) -> Result<(), MarkdownError> {
+    let mut rest = value;
+    while let Some(start) = rest.find('{') {
+        let placeholder = match rest[start..].find('}') {
+            Some(end) => &rest[start + 1..start + end],
+            None => {
+                return Err(MarkdownError::new(&format!(
+                    "unclosed placeholder in front matter \"{}\"",
+                    key
+                )))
+            }
+        };
+        if !PLACEHOLDERS.contains(&placeholder) {
+            return Err(MarkdownError::new(&format!(
+                "unknown placeholder {{{}}} in front matter \"{}\"; expected one of {{{}}}",
+                placeholder,
+                key,
+                PLACEHOLDERS.join("}, {")
+            )));
+        }
+        rest = &rest[start + placeholder.len() + 2..];
+    }
+    Ok(())
+}
+
+// Returns the root type name for the queries, mutations, and subscriptions
+// categories, or None for any other category.
+fn root_type_name(schema: &Schema, category: &str) -> Option<Option<String>> {
+    match category {
+        "queries" => Some(schema.get_query_name()),
+        "mutations" => Some(schema.get_mutation_name()),
+        "subscriptions" => Some(schema.get_subscription_name()),
+        _ => None,
+    }
+}
+
+fn category_title(schema: &Schema, category: &str) -> String {
+    match root_type_name(schema, category) {
+        Some(type_name) => type_name.unwrap_or_default(),
+        None if category == "directives" => "Directives".to_string(),
+        None if category == "deprecations" => "Deprecations".to_string(),
+        None => TYPE_CATEGORIES
+            .iter()
+            .find(|(name, _, _)| *name == category)
+            .map(|(_, title, _)| title.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+// Lists the (label, anchor) pairs for everything a category documents, in the
+// order they're rendered.
+fn category_entries(schema: &Schema, category: &str) -> Vec<(String, String)> {
+    match root_type_name(schema, category) {
+        Some(type_name) => match type_name.and_then(|name| schema.get_type(&name)) {
+            Some(Type {
+                name: Some(type_name),
+                fields: Some(fields),
+                ..
+            }) => fields
+                .iter()
+                .filter_map(|field| field.name.as_ref())
+                .map(|name| (name.clone(), field_anchor(type_name, name)))
+                .collect(),
+            _ => Vec::new(),
+        },
+        None => match TYPE_CATEGORIES
+            .iter()
+            .find(|(name, _, _)| *name == category)
+        {
+            Some((_, _, kind)) => {
+                let mut names: Vec<String> = schema
+                    .get_types_of_kind(kind)
+                    .iter()
+                    .filter_map(|typ| typ.name.clone())
+                    .collect();
+                names.sort();
+                names.into_iter().map(|name| (name.clone(), name)).collect()
+            }
+            None if category == "directives" => sorted_directives(schema)
+                .iter()
+                .filter_map(|directive| directive.name.as_deref())
+                .map(|name| (directive_anchor(name), directive_anchor(name)))
+                .collect(),
+            None if category == "deprecations" => deprecations(schema)
+                .into_iter()
+                .map(|(type_name, _)| (type_name.clone(), deprecations_anchor(&type_name)))
+                .collect(),
+            None => Vec::new(),
+        },
+    }
+}
+
+fn field_anchor(type_name: &str, field_name: &str) -> String {
+    format!("{}.{}", type_name, field_name)
+}
+
+fn directive_anchor(name: &str) -> String {
+    format!("@{}", name)
+}
+
+fn deprecations_anchor(type_name: &str) -> String {
+    format!("deprecations-{}", type_name)
+}
+
+// Builds the links between types: type references link to other files when the
+// output is split into multiple files, or to anchors within the same file
+// otherwise.
+struct Links<'a> {
+    schema: &'a Schema,
+    multiple: bool,
+    references: HashMap<String, Vec<Reference>>,
+    // The category each type is documented in, by type name
+    categories: HashMap<&'a str, &'static str>,
+    // The category of each root type, whose fields have their own sections
+    roots: HashMap<String, &'static str>,
+}
+
+impl<'a> Links<'a> {
+    fn new(schema: &'a Schema, multiple: bool) -> Links<'a> {
+        let mut categories = HashMap::new();
+        for typ in schema.types.iter().flatten() {
+            let category = TYPE_CATEGORIES
+                .iter()
+                .find(|(_, _, kind)| typ.kind.as_deref() == Some(kind));
+            if let (Some(name), Some((category, _, _))) = (&typ.name, category) {
+                categories.insert(name.as_str(), *category);
+            }
+        }
+
+        let mut roots = HashMap::new();
+        for category in CATEGORIES.iter() {
+            if let Some(Some(name)) = root_type_name(schema, category) {
+                roots.entry(name).or_insert(*category);
+            }
+        }
+
+        Links {
+            schema,
+            multiple,
+            references: schema.get_references(),
+            categories,
+            roots,
+        }
+    }
+
+    fn is_root(&self, type_name: &str) -> bool {
+        self.roots.contains_key(type_name)
+    }
+
+    fn target(&self, category: &str, anchor: &str) -> String {
+        if self.multiple {
+            format!("{}.md#{}", category, anchor)
+        } else {
+            format!("#{}", anchor)
+        }
+    }
+
+    fn type_target(&self, name: &str) -> Option<String> {
+        self.categories
+            .get(name)
+            .map(|category| self.target(category, name))
+    }
+
+    // Links a type name to where its type is documented, or returns the name
+    // unchanged when the schema doesn't define it.
+    fn type_link(&self, name: &str) -> String {
+        match self.type_target(name) {
+            Some(target) => to_link(name, &target),
+            None => name.to_string(),
+        }
+    }
+
+    // Links to a field, input field, or enum value: root fields have their own
+    // sections, and other members are anchored in their type's tables.
+    fn field_link(&self, type_name: &str, field_name: &str, text: &str) -> String {
+        let category = self
+            .roots
+            .get(type_name)
+            .or_else(|| self.categories.get(type_name));
+
+        match category {
+            Some(category) => to_link(
+                text,
+                &self.target(category, &field_anchor(type_name, field_name)),
+            ),
+            None => text.to_string(),
+        }
+    }
+
+    // Links to a directive's section by its anchor.
+    fn directive_link(&self, anchor: &str, text: &str) -> String {
+        to_link(text, &self.target("directives", anchor))
+    }
+
+    fn reference_to_markdown(&self, reference: &Reference) -> String {
+        match reference {
+            Reference::Field {
+                type_name,
+                field_name,
+            } => format!(
+                "{} field",
+                self.field_link(type_name, field_name, &field_anchor(type_name, field_name))
+            ),
+            Reference::Argument {
+                type_name,
+                field_name,
+                argument_name,
+            } => format!(
+                "{} argument",
+                self.field_link(
+                    type_name,
+                    field_name,
+                    &format!("{}({})", field_anchor(type_name, field_name), argument_name)
+                )
+            ),
+            Reference::InputField {
+                type_name,
+                field_name,
+            } => format!(
+                "{} input field",
+                self.field_link(type_name, field_name, &field_anchor(type_name, field_name))
+            ),
+            Reference::Interface { type_name } => {
+                format!("{} implements it", self.type_link(type_name))
+            }
+            Reference::UnionMember { type_name } => {
+                format!("{} union member", self.type_link(type_name))
+            }
+        }
+    }
+}
+
+fn schema_type_to_markdown(schema: &Schema, type_name: Option<String>, links: &Links) -> String {
+    let mut s = String::new();
+
+    if let Some(typ) = type_name.and_then(|name| schema.get_type(&name)) {
+        match &typ.name {
+            Some(name) => s.push_str(&to_header(1, &name)),
+            None => {}
+        }
+
+        match &typ.description {
+            Some(description) => s.push_str(&to_description(&description)),
+            None => {}
+        }
+
+        match &typ.fields {
+            Some(fields) => {
+                let type_name = typ.name.as_deref().unwrap_or_default();
+                for field in fields.iter() {
+                    s.push_str(&field_to_markdown(type_name, field, links));
+                }
+            }
+            None => {}
+        }
+    }
+
+    s
+}
+
+fn types_to_markdown(schema: &Schema, title: &str, kind: &str, links: &Links) -> String {
+    let mut s = String::new();
+
+    let mut types = schema.get_types_of_kind(kind);
+
+    if !types.is_empty() {
+        s.push_str(&to_header(1, title));
+
+        types.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for typ in types.iter() {
+            s.push_str(&type_to_markdown(typ, links));
+        }
+    }
+
+    s
 }
 
-impl Markdown {
-    pub fn with_front_matter(front_matter: Option<String>) -> Result<Markdown, Box<dyn Error>> {
-        Ok(Markdown { front_matter })
-    }
+fn type_to_markdown(typ: &Type, links: &Links) -> String {
+    let mut s = String::new();
+
+    match &typ.name {
+        Some(name) => {
+            s.push_str(&to_anchor(name));
+            s.push_str(&to_header(2, &name));
+        }
+        None => {}
+    }
+
+    match &typ.description {
+        Some(description) => s.push_str(&to_description(&description)),
+        None => {}
+    }
+
+    if let Some(url) = &typ.specified_by_url {
+        s.push_str(&to_label("Specified by", &to_link(url, url)));
+    }
+
+    if typ.is_one_of == Some(true) {
+        s.push_str(&to_label("One of", "Exactly one input field must be set"));
+    }
+
+    // The definition lists members by name, like the tables below.
+    let mut sorted = typ.clone();
+    sorted.normalize();
+    if let Some(sdl) = type_to_sdl(&sorted) {
+        s.push_str(&to_header(3, "Definition"));
+        s.push_str(&to_code_block("graphql", &sdl));
+    }
+
+    if let Some(interfaces) = &typ.interfaces {
+        if !interfaces.is_empty() {
+            s.push_str(&to_header(3, "Implements"));
+            let mut names: Vec<&str> = interfaces
+                .iter()
+                .filter_map(|interface| interface.name.as_deref())
+                .collect();
+            names.sort_unstable();
+            let items: Vec<String> = names.iter().map(|name| links.type_link(name)).collect();
+            s.push_str(&to_list(
+                &items.iter().map(String::as_str).collect::<Vec<&str>>(),
+            ));
+        }
+    }
+
+    match &typ.fields {
+        Some(fields) => {
+            s.push_str(&to_header(3, "Fields"));
+            let mut sorted = fields.to_vec();
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+            s.push_str(&to_markdown_table(
+                vec![
+                    "Name".to_string(),
+                    "Type".to_string(),
+                    "Description".to_string(),
+                ],
+                &sorted,
+                links,
+                member_anchors(typ, links),
+            ));
+        }
+        None => {}
+    }
+
+    if let Some(fields) = &typ.fields {
+        let mut sorted: Vec<&Field> = fields
+            .iter()
+            .filter(|field| field.args.as_ref().is_some_and(|args| !args.is_empty()))
+            .collect();
+        if !sorted.is_empty() {
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+            s.push_str(&to_header(3, "Arguments"));
+            for field in sorted.iter() {
+                s.push_str(&to_header(4, field.name.as_deref().unwrap_or_default()));
+                s.push_str(&inputs_table(
+                    field.args.as_deref().unwrap_or_default(),
+                    links,
+                    None,
+                ));
+            }
+        }
+    }
+
+    match &typ.inputs {
+        Some(inputs) => {
+            s.push_str(&to_header(3, "Inputs"));
+            s.push_str(&inputs_table(inputs, links, member_anchors(typ, links)));
+        }
+        None => {}
+    }
+
+    match &typ.enums {
+        Some(enums) => {
+            s.push_str(&to_header(3, "Values"));
+            let mut sorted = enums.to_vec();
+            sorted.sort_by(|a, b| a.name.cmp(&b.name));
+            s.push_str(&to_markdown_table(
+                vec![
+                    "Name".to_string(),
+                    "Description".to_string(),
+                    "Deprecated".to_string(),
+                ],
+                &sorted,
+                links,
+                member_anchors(typ, links),
+            ));
+        }
+        None => {}
+    }
+
+    match &typ.possible_types {
+        Some(possible_types) => {
+            let title = if typ.kind.as_deref() == Some("UNION") {
+                "Possible types"
+            } else {
+                "Implemented by"
+            };
+            s.push_str(&to_header(3, title));
+            let mut names: Vec<&str> = possible_types
+                .iter()
+                .map(|typ| match &typ.name {
+                    Some(name) => name,
+                    None => "",
+                })
+                .collect();
+            // Introspection only lists the objects that implement an interface,
+            // so add the interfaces that implement it too.
+            if typ.kind.as_deref() == Some("INTERFACE") {
+                for other in links.schema.get_types_of_kind("INTERFACE") {
+                    let implements = other
+                        .interfaces
+                        .iter()
+                        .flatten()
+                        .any(|interface| interface.name == typ.name);
+                    if let (true, Some(name)) = (implements, &other.name) {
+                        if !names.contains(&name.as_str()) {
+                            names.push(name);
+                        }
+                    }
+                }
+            }
+            names.sort();
+            let items: Vec<String> = names
+                .iter()
+                .map(|name| possible_type_to_markdown(name, links))
+                .collect();
+            s.push_str(&to_list(
+                &items.iter().map(String::as_str).collect::<Vec<&str>>(),
+            ));
+        }
+        None => {}
+    }
+
+    if let Some(references) = typ
+        .name
+        .as_ref()
+        .and_then(|name| links.references.get(name))
+    {
+        s.push_str(&to_header(3, "Referenced by"));
+        let items: Vec<String> = references
+            .iter()
+            .map(|reference| links.reference_to_markdown(reference))
+            .collect();
+        s.push_str(&to_list(
+            &items.iter().map(String::as_str).collect::<Vec<&str>>(),
+        ));
+    }
+
+    s
+}
+
+// Links a union member or interface implementation, noting when it's an
+// interface. GraphQL doesn't allow deprecating a type, so there's no
+// deprecation to note.
+fn possible_type_to_markdown(name: &str, links: &Links) -> String {
+    if links.categories.get(name) == Some(&"interfaces") {
+        format!("{} _(interface)_", links.type_link(name))
+    } else {
+        links.type_link(name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum DeprecatedKind {
+    Field,
+    Argument,
+    InputField,
+    EnumValue,
+}
+
+impl fmt::Display for DeprecatedKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeprecatedKind::Field => write!(f, "field"),
+            DeprecatedKind::Argument => write!(f, "argument"),
+            DeprecatedKind::InputField => write!(f, "input field"),
+            DeprecatedKind::EnumValue => write!(f, "enum value"),
+        }
+    }
+}
+
+// Something deprecated in a type or directive: a field, argument, input
+// field, or enum value.
+struct Deprecated {
+    kind: DeprecatedKind,
+    name: String,
+    // The field an argument belongs to, or None for a directive's argument
+    field_name: Option<String>,
+    reason: Option<String>,
+}
+
+impl Deprecated {
+    fn new(kind: DeprecatedKind, name: &Option<String>, reason: &Option<String>) -> Deprecated {
+        Deprecated {
+            kind,
+            name: name.clone().unwrap_or_default(),
+            field_name: None,
+            reason: reason.clone(),
+        }
+    }
+}
+
+// Lists everything deprecated, grouped by type name and then by directive
+// name, each in name order.
+fn deprecations(schema: &Schema) -> Vec<(String, Vec<Deprecated>)> {
+    let mut types: Vec<&Type> = schema.types.iter().flatten().collect();
+    types.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut grouped = Vec::new();
+    for typ in types.iter() {
+        let type_name = match &typ.name {
+            Some(name) => name,
+            None => continue,
+        };
+        let mut items = Vec::new();
+
+        let mut fields: Vec<&Field> = typ.fields.iter().flatten().collect();
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        for field in fields.iter() {
+            if field.is_deprecated == Some(true) {
+                items.push(Deprecated::new(
+                    DeprecatedKind::Field,
+                    &field.name,
+                    &field.deprecation_reason,
+                ));
+            }
+            for mut item in deprecated_args(field.args.as_deref().unwrap_or_default()) {
+                item.field_name = field.name.clone();
+                items.push(item);
+            }
+        }
+
+        let mut inputs: Vec<&Input> = typ.inputs.iter().flatten().collect();
+        inputs.sort_by(|a, b| a.name.cmp(&b.name));
+        for input in inputs
+            .iter()
+            .filter(|input| input.is_deprecated == Some(true))
+        {
+            items.push(Deprecated::new(
+                DeprecatedKind::InputField,
+                &input.name,
+                &input.deprecation_reason,
+            ));
+        }
+
+        let mut values: Vec<&Enum> = typ.enums.iter().flatten().collect();
+        values.sort_by(|a, b| a.name.cmp(&b.name));
+        for value in values
+            .iter()
+            .filter(|value| value.is_deprecated == Some(true))
+        {
+            items.push(Deprecated::new(
+                DeprecatedKind::EnumValue,
+                &value.name,
+                &value.deprecation_reason,
+            ));
+        }
+
+        if !items.is_empty() {
+            grouped.push((type_name.clone(), items));
+        }
+    }
+
+    for directive in sorted_directives(schema) {
+        let items = deprecated_args(directive.args.as_deref().unwrap_or_default());
+        if let (Some(name), false) = (&directive.name, items.is_empty()) {
+            grouped.push((directive_anchor(name), items));
+        }
+    }
+
+    grouped
+}
+
+fn deprecated_args(args: &[Input]) -> Vec<Deprecated> {
+    let mut args: Vec<&Input> = args.iter().collect();
+    args.sort_by(|a, b| a.name.cmp(&b.name));
+    args.iter()
+        .filter(|arg| arg.is_deprecated == Some(true))
+        .map(|arg| Deprecated::new(DeprecatedKind::Argument, &arg.name, &arg.deprecation_reason))
+        .collect()
+}
+
+fn deprecations_to_markdown(schema: &Schema, links: &Links) -> String {
+    let mut s = String::new();
+
+    let grouped = deprecations(schema);
+
+    if !grouped.is_empty() {
+        s.push_str(&to_header(1, "Deprecations"));
+
+        for (group_name, items) in grouped.iter() {
+            s.push_str(&to_anchor(&deprecations_anchor(group_name)));
+            s.push_str(&to_header(2, group_name));
+            s.push_str(&to_table_row(&[
+                "Name".to_string(),
+                "Kind".to_string(),
+                "Reason".to_string(),
+            ]));
+            s.push_str(&to_table_separator(3));
+            for item in items.iter() {
+                let name = match (item.kind, &item.field_name) {
+                    (DeprecatedKind::Argument, Some(field_name)) => links.field_link(
+                        group_name,
+                        field_name,
+                        &format!("{}({})", field_anchor(group_name, field_name), item.name),
+                    ),
+                    // Directive groups are named by the directive's anchor.
+                    (DeprecatedKind::Argument, None) => {
+                        links.directive_link(group_name, &format!("{}({})", group_name, item.name))
+                    }
+                    _ => links.field_link(
+                        group_name,
+                        &item.name,
+                        &field_anchor(group_name, &item.name),
+                    ),
+                };
+                s.push_str(&to_table_row(&[
+                    name,
+                    item.kind.to_string(),
+                    to_safe_string(&item.reason),
+                ]));
+            }
+            s.push('\n');
+        }
+    }
+
+    s
+}
+
+fn sorted_directives(schema: &Schema) -> Vec<&Directive> {
+    let mut directives: Vec<&Directive> = schema.directives.iter().flatten().collect();
+    directives.sort_by(|a, b| a.name.cmp(&b.name));
+    directives
+}
+
+fn directives_to_markdown(schema: &Schema, links: &Links) -> String {
+    let mut s = String::new();
+
+    let directives = sorted_directives(schema);
+
+    if !directives.is_empty() {
+        s.push_str(&to_header(1, "Directives"));
+
+        for directive in directives.iter() {
+            s.push_str(&directive_to_markdown(directive, links));
+        }
+    }
+
+    s
+}
+
+fn directive_to_markdown(directive: &Directive, links: &Links) -> String {
+    let mut s = String::new();
+
+    if let Some(name) = &directive.name {
+        let name = directive_anchor(name);
+        s.push_str(&to_anchor(&name));
+        s.push_str(&to_header(2, &name));
+    }
+
+    if let Some(description) = &directive.description {
+        s.push_str(&to_description(description));
+    }
+
+    if let Some(locations) = &directive.locations {
+        s.push_str(&to_label("Locations", &locations.join(", ")));
+    }
+
+    if let Some(is_repeatable) = directive.is_repeatable {
+        s.push_str(&to_label(
+            "Repeatable",
+            if is_repeatable { "yes" } else { "no" },
+        ));
+    }
+
+    if let Some(args) = &directive.args {
+        if !args.is_empty() {
+            s.push_str(&to_header(3, "Arguments"));
+            s.push_str(&inputs_table(args, links, None));
+        }
+    }
+
+    s
+}
+
+// Renders input fields or arguments as a table, sorted by name.
+fn inputs_table(inputs: &[Input], links: &Links, anchors: Option<&str>) -> String {
+    let mut sorted = inputs.to_vec();
+    sorted.sort_by(|a, b| a.name.cmp(&b.name));
+    to_markdown_table(
+        vec![
+            "Name".to_string(),
+            "Type".to_string(),
+            "Description".to_string(),
+            "Default Value".to_string(),
+        ],
+        &sorted,
+        links,
+        anchors,
+    )
+}
+
+// The type name to anchor a type's fields, input fields, or enum values with,
+// or None for a root type, whose fields are anchored in their own sections.
+fn member_anchors<'a>(typ: &'a Type, links: &Links) -> Option<&'a str> {
+    typ.name.as_deref().filter(|name| !links.is_root(name))
+}
+
+// Renders items as a table. When anchors names a type, each row starts with
+// an anchor for the item within that type.
+fn to_markdown_table(
+    headers: Vec<String>,
+    items: &[impl TableItem],
+    links: &Links,
+    anchors: Option<&str>,
+) -> String {
+    let mut s = String::new();
+    s.push_str(&to_table_row(&headers));
+    s.push_str(&to_table_separator(headers.len()));
+
+    for item in items.iter() {
+        let mut cells = item.table_fields(&|name| links.type_link(name));
+        if let (Some(type_name), Some(name), Some(cell)) = (anchors, item.name(), cells.first_mut())
+        {
+            cell.insert_str(0, &to_inline_anchor(&field_anchor(type_name, name)));
+        }
+        s.push_str(&to_table_row(&cells));
+    }
+    s.push_str("\n");
+    s
+}
+
+fn field_to_markdown(type_name: &str, field: &Field, links: &Links) -> String {
+    let mut s = String::new();
+
+    match &field.name {
+        Some(name) => {
+            s.push_str(&to_anchor(&field_anchor(type_name, name)));
+            s.push_str(&to_header(2, &name));
+        }
+        None => {}
+    }
+
+    if field.is_deprecated == Some(true) {
+        match &field.deprecation_reason {
+            Some(reason) => s.push_str(&to_label("Deprecated", reason)),
+            None => s.push_str(&to_notice("Deprecated")),
+        }
+    }
+
+    match &field.description {
+        Some(description) => s.push_str(&to_description(&description)),
+        None => {}
+    }
+
+    match &field.field_type {
+        Some(typ) => s.push_str(&to_label(
+            "Type",
+            &typ.linked_name(&|name| links.type_link(name)),
+        )),
+        None => {}
+    }
+
+    match &field.args {
+        Some(args) => {
+            if !args.is_empty() {
+                s.push_str(&to_header(3, "Arguments"));
+                s.push_str(&inputs_table(args, links, None));
+            }
+        }
+        None => {}
+    }
+
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::TypeRef;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_with_front_matter_should_return_ok_when_none() {
+        assert!(Markdown::with_front_matter(None).is_ok());
+    }
+
+    #[test]
+    fn test_with_front_matter_should_return_ok_when_some() {
+        assert!(Markdown::with_front_matter(Some("fm:foo".to_string())).is_ok());
+    }
+
+    #[test]
+    fn test_parse_front_matter_should_split_on_first_colon() {
+        assert_eq!(
+            vec![
+                ("id".to_string(), "{slug}".to_string()),
+                ("url".to_string(), "https://example.com".to_string()),
+            ],
+            parse_front_matter(" id: {slug} ; url:https://example.com;").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_front_matter_should_return_error_when_malformed() {
+        let error = |front_matter| parse_front_matter(front_matter).unwrap_err().to_string();
+        assert_eq!(
+            "invalid front matter \"title\": expected key:value",
+            error("id:x;title")
+        );
+        assert_eq!("invalid front matter \":x\": missing key", error(":x"));
+        assert_eq!(
+            "invalid front matter key \"side bar\": keys cannot contain whitespace",
+            error("side bar:x")
+        );
+        assert_eq!("duplicate front matter key \"id\"", error("id:a;id:b"));
+        assert_eq!(
+            "unknown placeholder {name} in front matter \"title\"; expected one of {title}, {slug}, {category}",
+            error("title:{name}")
+        );
+        assert_eq!(
+            "unclosed placeholder in front matter \"title\"",
+            error("title:{title")
+        );
+    }
+
+    #[test]
+    fn test_generate_from_schema_should_add_front_matter_to_each_file() {
+        let markdown = Markdown::with_front_matter(Some(
+            "id:{slug};title:{title};sidebar_label:{title} ({category})".to_string(),
+        ))
+        .unwrap();
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: Some(vec![Type {
+                name: Some("Date".to_string()),
+                kind: Some("SCALAR".to_string()),
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                ..Default::default()
+            }]),
+            directives: None,
+            ..Default::default()
+        };
+        let map = markdown.generate_from_schema(schema);
+        assert_eq!(
+            "---\nid: \"scalars\"\ntitle: \"Scalars\"\nsidebar_label: \"Scalars (scalars)\"\n---\n\n# Scalars\n\n<a id=\"Date\"></a>\n\n## Date\n\n### Definition\n\n```graphql\nscalar Date\n```\n\n",
+            map["scalars"]
+        );
+        assert_eq!("", map["objects"]);
+        assert!(markdown
+            .generate_single_from_schema(schema, "api")
+            .starts_with("---\nid: \"api\"\ntitle: \"Schema\"\nsidebar_label: \"Schema (schema)\"\n---\n\n# Table of Contents\n\n"));
+    }
+
+    #[test]
+    fn test_type_link_should_link_to_anchor_or_category_file() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: Some(vec![Type {
+                name: Some("Team".to_string()),
+                kind: Some("OBJECT".to_string()),
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                ..Default::default()
+            }]),
+            directives: None,
+            ..Default::default()
+        };
+        assert_eq!("[Team](#Team)", Links::new(schema, false).type_link("Team"));
+        assert_eq!(
+            "[Team](objects.md#Team)",
+            Links::new(schema, true).type_link("Team")
+        );
+        assert_eq!("Unknown", Links::new(schema, true).type_link("Unknown"));
+    }
+
+    #[test]
+    fn test_type_to_markdown_should_list_references_with_links() {
+        let schema = &Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")]).unwrap();
+        let markdown = type_to_markdown(
+            schema.get_type("Player").unwrap(),
+            &Links::new(schema, true),
+        );
+        assert!(markdown.ends_with(
+            r#"### Referenced by
+
+* [Mutation.addPlayer](mutations.md#Mutation.addPlayer) field
+* [Query.players](queries.md#Query.players) field
+* [Team.players](objects.md#Team.players) field
+* [Member](unions.md#Member) union member
+
+"#
+        ));
+    }
+
+    #[test]
+    fn test_directives_to_markdown_should_return_empty_when_none() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: Some(vec![]),
+            ..Default::default()
+        };
+        assert_eq!(
+            "",
+            directives_to_markdown(schema, &Links::new(schema, false))
+        );
+    }
+
+    #[test]
+    fn test_directives_to_markdown_should_return_markdown() {
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: Some(vec![Directive {
+                name: Some("cached".to_string()),
+                description: Some("Caches the field".to_string()),
+                locations: Some(vec!["FIELD_DEFINITION".to_string(), "OBJECT".to_string()]),
+                args: Some(vec![Input {
+                    name: Some("ttl".to_string()),
+                    description: Some("Seconds to cache".to_string()),
+                    input_type: Some(TypeRef {
+                        name: Some("Int".to_string()),
+                        kind: Some("SCALAR".to_string()),
+                        of_type: None,
+                    }),
+                    default_value: Some("60".to_string()),
+                    ..Default::default()
+                }]),
+                is_repeatable: Some(true),
+            }]),
+            ..Default::default()
+        };
+        assert_eq!(
+            r#"# Directives
+
+<a id="@cached"></a>
+
+## @cached
+
+> Caches the field
+
+**Locations:** FIELD_DEFINITION, OBJECT
+
+**Repeatable:** yes
+
+### Arguments
+
+| Name | Type | Description | Default Value |
+| --- | --- | --- | --- |
+| ttl | Int | Seconds to cache | 60 |
+
+"#,
+            directives_to_markdown(schema, &Links::new(schema, false))
+        );
+    }
+
+    #[test]
+    fn test_type_to_markdown_should_list_implemented_interfaces() {
+        let schema = &Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")]).unwrap();
+        let markdown =
+            type_to_markdown(schema.get_type("Team").unwrap(), &Links::new(schema, true));
+        assert!(markdown.starts_with(
+            r#"<a id="Team"></a>
+
+## Team
+
+### Definition
+
+```graphql
+type Team implements Node {
+  id: ID!
+  name: String!
+  players: [Player!]!
+}
+```
+
+### Implements
+
+* [Node](interfaces.md#Node)
+
+### Fields
+"#
+        ));
+    }
+
+    #[test]
+    fn test_type_to_markdown_should_list_interfaces_of_interface() {
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            "interface Node { id: ID! }\ninterface Named implements Node { id: ID! name: String }\n"
+                .to_string(),
+        )])
+        .unwrap();
+        let markdown = type_to_markdown(
+            schema.get_type("Named").unwrap(),
+            &Links::new(schema, false),
+        );
+        assert!(markdown.contains("### Implements\n\n* [Node](#Node)\n\n"));
+    }
+
+    #[test]
+    fn test_type_to_markdown_should_list_union_possible_types() {
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            r#"
+            type Player { id: ID }
+            type Coach { id: ID }
+            union Member = Player | Coach
+            "#
+            .to_string(),
+        )])
+        .unwrap();
+        let markdown = type_to_markdown(
+            schema.get_type("Member").unwrap(),
+            &Links::new(schema, false),
+        );
+        assert_eq!(
+            r#"<a id="Member"></a>
+
+## Member
+
+### Definition
+
+```graphql
+union Member = Coach | Player
+```
+
+### Possible types
+
+* [Coach](#Coach)
+* [Player](#Player)
+
+"#,
+            markdown
+        );
+    }
+
+    #[test]
+    fn test_type_to_markdown_should_list_interface_implementations() {
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            r#"
+            interface Node { id: ID! }
+            interface Named implements Node { id: ID! name: String }
+            type Player implements Node & Named { id: ID! name: String }
+            "#
+            .to_string(),
+        )])
+        .unwrap();
+        let markdown =
+            type_to_markdown(schema.get_type("Node").unwrap(), &Links::new(schema, false));
+        assert!(markdown.contains(
+            "### Implemented by\n\n* [Named](#Named) _(interface)_\n* [Player](#Player)\n\n"
+        ));
+    }
+
+    #[test]
+    fn test_type_to_markdown_should_list_field_arguments() {
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            r#"
+            interface Node { id: ID! }
+            type Repository implements Node {
+              id: ID!
+              "The issues in the repository"
+              issues(first: Int = 10, "Cursor to start after" after: String): [String!]!
+            }
+            "#
+            .to_string(),
+        )])
+        .unwrap();
+        assert_eq!(
+            r#"<a id="Repository"></a>
+
+## Repository
+
+### Definition
+
+```graphql
+type Repository implements Node {
+  id: ID!
+  """The issues in the repository"""
+  issues(
+    """Cursor to start after"""
+    after: String
+    first: Int = 10
+  ): [String!]!
+}
+```
+
+### Implements
+
+* [Node](#Node)
+
+### Fields
+
+| Name | Type | Description |
+| --- | --- | --- |
+| <a id="Repository.id"></a>id | [ID](#ID)! |  |
+| <a id="Repository.issues"></a>issues | [[String](#String)!]! | The issues in the repository |
+
+### Arguments
+
+#### issues
 
-    pub fn generate_from_schema(&self, schema: &Schema) -> HashMap<String, String> {
-        let mut contents: HashMap<String, String> = HashMap::new();
+| Name | Type | Description | Default Value |
+| --- | --- | --- | --- |
+| after | [String](#String) | Cursor to start after |  |
+| first | [Int](#Int) |  | 10 |
 
-        contents.insert(
-            "queries".to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name()),
-        );
-        contents.insert(
-            "mutations".to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name()),
-        );
-        contents.insert(
-            "subscriptions".to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name()),
+"#,
+            type_to_markdown(
+                schema.get_type("Repository").unwrap(),
+                &Links::new(schema, false)
+            )
         );
-        contents.insert(
-            "inputs".to_string(),
-            types_to_markdown(schema, "Inputs", "INPUT_OBJECT"),
-        );
-        contents.insert(
-            "objects".to_string(),
-            types_to_markdown(schema, "Objects", "OBJECT"),
-        );
-        contents.insert(
-            "enums".to_string(),
-            types_to_markdown(schema, "Enums", "ENUM"),
-        );
-        contents.insert(
-            "interfaces".to_string(),
-            types_to_markdown(schema, "Interfaces", "INTERFACE"),
-        );
-        contents.insert(
-            "unions".to_string(),
-            types_to_markdown(schema, "Unions", "UNION"),
-        );
-        contents.insert(
-            "scalars".to_string(),
-            types_to_markdown(schema, "Scalars", "SCALAR"),
-        );
-
-        contents
     }
-}
 
-fn schema_type_to_markdown(schema: &Schema, type_name: Option<String>) -> String {
-    let mut s = String::new();
+    #[test]
+    fn test_field_to_markdown_should_show_deprecation_reason() {
+        let schema = &Schema::from_schema(&[PathBuf::from("testdata/schema.graphql")]).unwrap();
+        let field = Field {
+            name: Some("oldPlayers".to_string()),
+            description: Some("The players".to_string()),
+            args: None,
+            field_type: None,
+            is_deprecated: Some(true),
+            deprecation_reason: Some("Use `players`".to_string()),
+        };
+        assert_eq!(
+            r#"<a id="Query.oldPlayers"></a>
 
-    if let Some(typ) = type_name.and_then(|name| schema.get_type(&name)) {
-        match &typ.name {
-            Some(name) => s.push_str(&to_header(1, &name)),
-            None => {}
-        }
+## oldPlayers
 
-        match &typ.description {
-            Some(description) => s.push_str(&to_description(&description)),
-            None => {}
-        }
+**Deprecated:** Use `players`
 
-        match &typ.fields {
-            Some(fields) => {
-                for field in fields.iter() {
-                    s.push_str(&field_to_markdown(field));
-                }
-            }
-            None => {}
-        }
+> The players
+
+"#,
+            field_to_markdown("Query", &field, &Links::new(schema, false))
+        );
     }
 
-    s
-}
+    #[test]
+    fn test_deprecations_to_markdown_should_group_by_type_and_directive() {
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            r#"
+            type Query {
+              players(team: ID @deprecated(reason: "Use teamId"), teamId: ID): [Player]
+            }
+            type Player {
+              name: String
+              nickname: String @deprecated(reason: "Use `name`")
+              position: Position
+            }
+            enum Position { GOALIE SWEEPER @deprecated(reason: "Not a position") }
+            input PlayerFilter { legacy: Int @deprecated }
+            directive @cached(ttl: Int, maxAge: Int @deprecated(reason: "Use ttl")) on FIELD_DEFINITION
+            "#
+            .to_string(),
+        )])
+        .unwrap();
+        assert_eq!(
+            r#"# Deprecations
 
-fn types_to_markdown(schema: &Schema, title: &str, kind: &str) -> String {
-    let mut s = String::new();
+<a id="deprecations-Player"></a>
 
-    let mut types = schema.get_types_of_kind(kind);
+## Player
 
-    if !types.is_empty() {
-        s.push_str(&to_header(1, title));
+| Name | Kind | Reason |
+| --- | --- | --- |
+| [Player.nickname](objects.md#Player.nickname) | field | Use `name` |
 
-        types.sort_by(|a, b| a.name.cmp(&b.name));
+<a id="deprecations-PlayerFilter"></a>
 
-        for typ in types.iter() {
-            s.push_str(&type_to_markdown(typ));
-        }
-    }
+## PlayerFilter
 
-    s
-}
+| Name | Kind | Reason |
+| --- | --- | --- |
+| [PlayerFilter.legacy](inputs.md#PlayerFilter.legacy) | input field | No longer supported |
 
-fn type_to_markdown(typ: &Type) -> String {
-    let mut s = String::new();
+<a id="deprecations-Position"></a>
 
-    match &typ.name {
-        Some(name) => s.push_str(&to_header(2, &name)),
-        None => {}
-    }
+## Position
 
-    match &typ.description {
-        Some(description) => s.push_str(&to_description(&description)),
-        None => {}
-    }
+| Name | Kind | Reason |
+| --- | --- | --- |
+| [Position.SWEEPER](enums.md#Position.SWEEPER) | enum value | Not a position |
 
-    match &typ.fields {
-        Some(fields) => {
-            s.push_str(&to_header(3, "Fields"));
-            let mut sorted = fields.to_vec();
-            sorted.sort_by(|a, b| a.name.cmp(&b.name));
-            s.push_str(&to_markdown_table(
-                vec![
-                    "Name".to_string(),
-                    "Type".to_string(),
-                    "Description".to_string(),
-                ],
-                &sorted,
-            ));
-        }
-        None => {}
-    }
+<a id="deprecations-Query"></a>
 
-    match &typ.inputs {
-        Some(inputs) => {
-            s.push_str(&to_header(3, "Inputs"));
-            let mut sorted = inputs.to_vec();
-            sorted.sort_by(|a, b| a.name.cmp(&b.name));
-            s.push_str(&to_markdown_table(
-                vec![
-                    "Name".to_string(),
-                    "Type".to_string(),
-                    "Description".to_string(),
-                    "Default Value".to_string(),
-                ],
-                &sorted,
-            ));
-        }
-        None => {}
-    }
+## Query
 
-    match &typ.enums {
-        Some(enums) => {
-            s.push_str(&to_header(3, "Values"));
-            let mut sorted = enums.to_vec();
-            sorted.sort_by(|a, b| a.name.cmp(&b.name));
-            s.push_str(&to_markdown_table(
-                vec![
-                    "Name".to_string(),
-                    "Description".to_string(),
-                    "Deprecated".to_string(),
-                ],
-                &sorted,
-            ));
-        }
-        None => {}
-    }
+| Name | Kind | Reason |
+| --- | --- | --- |
+| [Query.players(team)](queries.md#Query.players) | argument | Use teamId |
 
-    match &typ.possible_types {
-        Some(possible_types) => {
-            s.push_str(&to_header(3, "Implemented by"));
-            let mut names: Vec<&str> = possible_types
-                .iter()
-                .map(|typ| match &typ.name {
-                    Some(name) => name,
-                    None => "",
-                })
-                .collect();
-            names.sort();
-            s.push_str(&to_list(&names));
-        }
-        None => {}
-    }
+<a id="deprecations-@cached"></a>
 
-    s
-}
+## @cached
 
-fn to_markdown_table(headers: Vec<String>, items: &[impl TableItem]) -> String {
-    let mut s = String::new();
-    s.push_str(&to_table_row(&headers));
-    s.push_str(&to_table_separator(headers.len()));
+| Name | Kind | Reason |
+| --- | --- | --- |
+| [@cached(maxAge)](directives.md#@cached) | argument | Use ttl |
 
-    for item in items.iter() {
-        s.push_str(&to_table_row(&item.table_fields()));
+"#,
+            deprecations_to_markdown(schema, &Links::new(schema, true))
+        );
     }
-    s.push_str("\n");
-    s
-}
 
-fn field_to_markdown(field: &Field) -> String {
-    let mut s = String::new();
+    #[test]
+    fn test_type_to_markdown_should_show_specified_by_url_and_one_of() {
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            r#"
+            scalar UUID @specifiedBy(url: "https://tools.ietf.org/html/rfc4122")
+            input PlayerBy @oneOf { id: ID name: String }
+            "#
+            .to_string(),
+        )])
+        .unwrap();
+        let links = &Links::new(schema, false);
+        assert_eq!(
+            r#"<a id="UUID"></a>
 
-    match &field.name {
-        Some(name) => s.push_str(&to_header(2, &name)),
-        None => {}
-    }
+## UUID
 
-    match &field.is_deprecated {
-        Some(deprecated) => {
-            if *deprecated {
-                s.push_str(&to_notice("Deprecated"));
-            }
-        }
-        None => {}
-    }
+**Specified by:** [https://tools.ietf.org/html/rfc4122](https://tools.ietf.org/html/rfc4122)
 
-    match &field.description {
-        Some(description) => s.push_str(&to_description(&description)),
-        None => {}
-    }
+### Definition
 
-    match &field.field_type {
-        Some(typ) => s.push_str(&to_label("Type", &typ.decorated_name())),
-        None => {}
-    }
+```graphql
+scalar UUID @specifiedBy(url: "https://tools.ietf.org/html/rfc4122")
+```
 
-    match &field.args {
-        Some(args) => {
-            if !args.is_empty() {
-                s.push_str(&to_header(3, "Arguments"));
-                let mut sorted = args.to_vec();
-                sorted.sort_by(|a, b| a.name.cmp(&b.name));
-                s.push_str(&to_markdown_table(
-                    vec![
-                        "Name".to_string(),
-                        "Type".to_string(),
-                        "Description".to_string(),
-                        "Default Value".to_string(),
-                    ],
-                    &sorted,
-                ));
-            }
-        }
-        None => {}
+"#,
+            type_to_markdown(schema.get_type("UUID").unwrap(), links)
+        );
+        assert!(
+            type_to_markdown(schema.get_type("PlayerBy").unwrap(), links).contains(
+                "## PlayerBy\n\n**One of:** Exactly one input field must be set\n\n### Definition\n\n```graphql\ninput PlayerBy @oneOf {\n  id: ID\n  name: String\n}\n```\n\n### Inputs\n\n"
+            )
+        );
     }
 
-    s
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
     #[test]
-    fn test_with_front_matter_should_return_ok_when_none() {
-        assert!(Markdown::with_front_matter(None).is_ok());
+    fn test_generate_single_from_schema_should_start_with_schema_description() {
+        let markdown = Markdown::with_front_matter(None).unwrap();
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            "\"The league API\"\nschema { query: Query }\ntype Query { a: Int }\n".to_string(),
+        )])
+        .unwrap();
+        assert!(markdown
+            .generate_single_from_schema(schema, "schema")
+            .starts_with("> The league API\n\n# Table of Contents\n\n"));
     }
 
     #[test]
-    fn test_with_front_matter_should_return_ok_when_some() {
-        assert!(Markdown::with_front_matter(Some("fm:foo".to_string())).is_ok());
+    fn test_generate_from_schema_should_start_queries_with_schema_description() {
+        let markdown = Markdown::with_front_matter(Some("title:{title}".to_string())).unwrap();
+        let schema = &crate::sdl::parse_files(&[(
+            PathBuf::from("schema.graphql"),
+            "\"The league API\"\nschema { query: Query }\ntype Query { a: Int }\ntype Player { b: Int }\n"
+                .to_string(),
+        )])
+        .unwrap();
+        let map = markdown.generate_from_schema(schema);
+        assert!(map["queries"].starts_with("---\ntitle: \"Query\"\n---\n\n> The league API\n\n"));
+        assert!(!map["objects"].contains("The league API"));
     }
 
     #[test]
@@ -271,9 +1519,10 @@ mod tests {
             subscription_type: None,
             types: None,
             directives: None,
+            ..Default::default()
         };
         let map = markdown.generate_from_schema(schema);
-        assert_eq!(9, map.len());
+        assert_eq!(11, map.len());
         assert_eq!("".to_string(), map["queries"]);
         assert_eq!("".to_string(), map["mutations"]);
         assert_eq!("".to_string(), map["subscriptions"]);
@@ -283,6 +1532,130 @@ mod tests {
         assert_eq!("".to_string(), map["interfaces"]);
         assert_eq!("".to_string(), map["unions"]);
         assert_eq!("".to_string(), map["scalars"]);
+        assert_eq!("".to_string(), map["directives"]);
+        assert_eq!("".to_string(), map["deprecations"]);
+    }
+
+    #[test]
+    fn test_generate_single_from_schema_should_return_empty_when_empty_schema() {
+        let markdown = Markdown::with_front_matter(None).unwrap();
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            ..Default::default()
+        };
+        assert_eq!("", markdown.generate_single_from_schema(schema, "schema"));
+    }
+
+    #[test]
+    fn test_generate_single_from_schema_should_return_sections_in_order_with_toc() {
+        let markdown = Markdown::with_front_matter(None).unwrap();
+        let schema = &Schema {
+            query_type: Some(Type {
+                name: Some("Query".to_string()),
+                kind: None,
+                description: None,
+                fields: None,
+                inputs: None,
+                interfaces: None,
+                enums: None,
+                possible_types: None,
+                ..Default::default()
+            }),
+            mutation_type: None,
+            subscription_type: None,
+            types: Some(vec![
+                Type {
+                    name: Some("Query".to_string()),
+                    kind: Some("OBJECT".to_string()),
+                    description: None,
+                    fields: Some(vec![Field {
+                        name: Some("players".to_string()),
+                        description: None,
+                        args: None,
+                        field_type: None,
+                        is_deprecated: None,
+                        deprecation_reason: None,
+                    }]),
+                    inputs: None,
+                    interfaces: None,
+                    enums: None,
+                    possible_types: None,
+                    ..Default::default()
+                },
+                Type {
+                    name: Some("Date".to_string()),
+                    kind: Some("SCALAR".to_string()),
+                    description: None,
+                    fields: None,
+                    inputs: None,
+                    interfaces: None,
+                    enums: None,
+                    possible_types: None,
+                    ..Default::default()
+                },
+            ]),
+            directives: None,
+            ..Default::default()
+        };
+        assert_eq!(
+            r#"# Table of Contents
+
+* [Query](#queries)
+  * [players](#Query.players)
+* [Objects](#objects)
+  * [Query](#Query)
+* [Scalars](#scalars)
+  * [Date](#Date)
+
+<a id="queries"></a>
+
+# Query
+
+<a id="Query.players"></a>
+
+## players
+
+<a id="objects"></a>
+
+# Objects
+
+<a id="Query"></a>
+
+## Query
+
+### Definition
+
+```graphql
+type Query
+```
+
+### Fields
+
+| Name | Type | Description |
+| --- | --- | --- |
+| players |  |  |
+
+<a id="scalars"></a>
+
+# Scalars
+
+<a id="Date"></a>
+
+## Date
+
+### Definition
+
+```graphql
+scalar Date
+```
+
+"#,
+            markdown.generate_single_from_schema(schema, "schema")
+        );
     }
 
     #[test]
@@ -293,10 +1666,11 @@ mod tests {
             subscription_type: None,
             types: None,
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             "".to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name())
+            schema_type_to_markdown(schema, schema.get_query_name(), &Links::new(schema, false))
         );
     }
 
@@ -312,15 +1686,17 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }),
             mutation_type: None,
             subscription_type: None,
             types: None,
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             "".to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name())
+            schema_type_to_markdown(schema, schema.get_query_name(), &Links::new(schema, false))
         );
     }
 
@@ -336,6 +1712,7 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }),
             mutation_type: None,
             subscription_type: None,
@@ -355,21 +1732,25 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }]),
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             r#"# Query
 
 > The root query
 
+<a id="Query.players"></a>
+
 ## players
 
 > get the players
 
 "#
             .to_string(),
-            schema_type_to_markdown(schema, schema.get_query_name())
+            schema_type_to_markdown(schema, schema.get_query_name(), &Links::new(schema, false))
         );
     }
 
@@ -381,10 +1762,15 @@ mod tests {
             subscription_type: None,
             types: None,
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             "".to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name())
+            schema_type_to_markdown(
+                schema,
+                schema.get_mutation_name(),
+                &Links::new(schema, false)
+            )
         );
     }
 
@@ -401,14 +1787,20 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }),
             subscription_type: None,
             types: None,
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             "".to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name())
+            schema_type_to_markdown(
+                schema,
+                schema.get_mutation_name(),
+                &Links::new(schema, false)
+            )
         );
     }
 
@@ -425,6 +1817,7 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }),
             subscription_type: None,
             types: Some(vec![Type {
@@ -443,21 +1836,29 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }]),
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             r#"# Mutation
 
 > The root mutation
 
+<a id="Mutation.addPlayer"></a>
+
 ## addPlayer
 
 > add a player
 
 "#
             .to_string(),
-            schema_type_to_markdown(schema, schema.get_mutation_name())
+            schema_type_to_markdown(
+                schema,
+                schema.get_mutation_name(),
+                &Links::new(schema, false)
+            )
         );
     }
 
@@ -469,10 +1870,15 @@ mod tests {
             subscription_type: None,
             types: None,
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             "".to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name())
+            schema_type_to_markdown(
+                schema,
+                schema.get_subscription_name(),
+                &Links::new(schema, false)
+            )
         );
     }
 
@@ -491,13 +1897,19 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }),
             types: None,
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             "".to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name())
+            schema_type_to_markdown(
+                schema,
+                schema.get_subscription_name(),
+                &Links::new(schema, false)
+            )
         );
     }
 
@@ -515,6 +1927,7 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }),
             types: Some(vec![Type {
                 name: Some("Subscription".to_string()),
@@ -532,21 +1945,29 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }]),
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             r#"# Subscription
 
 > The root subscription
 
+<a id="Subscription.subscribePlayers"></a>
+
 ## subscribePlayers
 
 > subscribe to players
 
 "#
             .to_string(),
-            schema_type_to_markdown(schema, schema.get_subscription_name())
+            schema_type_to_markdown(
+                schema,
+                schema.get_subscription_name(),
+                &Links::new(schema, false)
+            )
         );
     }
 
@@ -582,26 +2003,37 @@ mod tests {
                 interfaces: None,
                 enums: None,
                 possible_types: None,
+                ..Default::default()
             }]),
             directives: None,
+            ..Default::default()
         };
         assert_eq!(
             r#"# Objects
 
+<a id="Player"></a>
+
 ## Player
 
 > A player
 
+### Definition
+
+```graphql
+"""A player"""
+type Player
+```
+
 ### Fields
 
 | Name | Type | Description |
 | --- | --- | --- |
-| firstName |  | The player's first name |
-| lastName |  | The player's last name |
+| <a id="Player.firstName"></a>firstName |  | The player's first name |
+| <a id="Player.lastName"></a>lastName |  | The player's last name |
 
 "#
             .to_string(),
-            types_to_markdown(schema, "Objects", "OBJECT")
+            types_to_markdown(schema, "Objects", "OBJECT", &Links::new(schema, false))
         );
     }
 
@@ -623,9 +2055,20 @@ mod tests {
                 is_deprecated: None,
                 deprecation_reason: None,
             }]),
+            ..Default::default()
+        };
+        let schema = &Schema {
+            query_type: None,
+            mutation_type: None,
+            subscription_type: None,
+            types: None,
+            directives: None,
+            ..Default::default()
         };
         assert_eq!(
-            r#"## Player
+            r#"<a id="Player"></a>
+
+## Player
 
 > This is a player
 
@@ -633,11 +2076,11 @@ mod tests {
 
 | Name | Type | Description |
 | --- | --- | --- |
-| id |  | The ID |
+| <a id="Player.id"></a>id |  | The ID |
 
 "#
             .to_string(),
-            type_to_markdown(typ)
+            type_to_markdown(typ, &Links::new(schema, false))
         );
     }
 }
@@ -0,0 +1,423 @@
+use super::schema::{Directive, Input, Schema, Type, TypeRef};
+
+pub const BUILT_IN_SCALARS: [&str; 5] = ["Boolean", "Float", "ID", "Int", "String"];
+pub const BUILT_IN_DIRECTIVES: [&str; 5] =
+    ["include", "skip", "deprecated", "specifiedBy", "oneOf"];
+pub const DEFAULT_DEPRECATION_REASON: &str = "No longer supported";
+
+// Prints the schema as SDL, leaving out the built-in scalars and directives
+// and the introspection types.
+pub fn schema_to_sdl(schema: &Schema) -> String {
+    let mut definitions = Vec::new();
+
+    if let Some(definition) = schema_definition_to_sdl(schema) {
+        definitions.push(definition);
+    }
+
+    for directive in schema.directives.iter().flatten() {
+        let name = directive.name.as_deref().unwrap_or_default();
+        if !BUILT_IN_DIRECTIVES.contains(&name) {
+            definitions.push(directive_to_sdl(directive));
+        }
+    }
+
+    for typ in schema.types.iter().flatten() {
+        let name = typ.name.as_deref().unwrap_or_default();
+        if !name.starts_with("__") && !BUILT_IN_SCALARS.contains(&name) {
+            definitions.extend(type_to_sdl(typ));
+        }
+    }
+
+    let mut sdl = definitions.join("\n\n");
+    sdl.push('\n');
+    sdl
+}
+
+// Prints a type definition, or nothing for a type without a known kind.
+// Fields, arguments, and input fields without a type are left out, since SDL
+// can't express them.
+pub fn type_to_sdl(typ: &Type) -> Option<String> {
+    let mut s = description_to_sdl(typ.description.as_deref(), "");
+    let name = typ.name.as_deref().unwrap_or_default();
+
+    match typ.kind.as_deref().unwrap_or_default() {
+        "SCALAR" => {
+            s.push_str(&format!("scalar {}", name));
+            if let Some(url) = &typ.specified_by_url {
+                s.push_str(&format!(" @specifiedBy(url: {})", to_string_value(url)));
+            }
+        }
+        "OBJECT" | "INTERFACE" => {
+            let keyword = if typ.kind.as_deref() == Some("OBJECT") {
+                "type"
+            } else {
+                "interface"
+            };
+            s.push_str(&format!("{} {}", keyword, name));
+            let interfaces: Vec<&str> = typ
+                .interfaces
+                .iter()
+                .flatten()
+                .filter_map(|interface| interface.name.as_deref())
+                .collect();
+            if !interfaces.is_empty() {
+                s.push_str(&format!(" implements {}", interfaces.join(" & ")));
+            }
+            let fields: Vec<String> = typ
+                .fields
+                .iter()
+                .flatten()
+                .filter(|field| field.field_type.is_some())
+                .map(|field| {
+                    format!(
+                        "{}  {}{}: {}{}",
+                        description_to_sdl(field.description.as_deref(), "  "),
+                        field.name.as_deref().unwrap_or_default(),
+                        args_to_sdl(field.args.as_deref().unwrap_or_default(), "  "),
+                        type_ref_to_sdl(&field.field_type),
+                        deprecated_to_sdl(field.is_deprecated, field.deprecation_reason.as_deref())
+                    )
+                })
+                .collect();
+            s.push_str(&block_to_sdl(&fields));
+        }
+        "UNION" => {
+            s.push_str(&format!("union {}", name));
+            let members: Vec<&str> = typ
+                .possible_types
+                .iter()
+                .flatten()
+                .filter_map(|member| member.name.as_deref())
+                .collect();
+            if !members.is_empty() {
+                s.push_str(&format!(" = {}", members.join(" | ")));
+            }
+        }
+        "ENUM" => {
+            s.push_str(&format!("enum {}", name));
+            let values: Vec<String> = typ
+                .enums
+                .iter()
+                .flatten()
+                .map(|value| {
+                    format!(
+                        "{}  {}{}",
+                        description_to_sdl(value.description.as_deref(), "  "),
+                        value.name.as_deref().unwrap_or_default(),
+                        deprecated_to_sdl(value.is_deprecated, value.deprecation_reason.as_deref())
+                    )
+                })
+                .collect();
+            s.push_str(&block_to_sdl(&values));
+        }
+        "INPUT_OBJECT" => {
+            s.push_str(&format!("input {}", name));
+            if typ.is_one_of == Some(true) {
+                s.push_str(" @oneOf");
+            }
+            let inputs: Vec<String> = typ
+                .inputs
+                .iter()
+                .flatten()
+                .filter(|input| input.input_type.is_some())
+                .map(|input| input_value_to_sdl(input, "  "))
+                .collect();
+            s.push_str(&block_to_sdl(&inputs));
+        }
+        _ => return None,
+    }
+
+    Some(s)
+}
+
+fn schema_definition_to_sdl(schema: &Schema) -> Option<String> {
+    let roots = [
+        ("query", schema.get_query_name(), "Query"),
+        ("mutation", schema.get_mutation_name(), "Mutation"),
+        (
+            "subscription",
+            schema.get_subscription_name(),
+            "Subscription",
+        ),
+    ];
+    let is_default = roots
+        .iter()
+        .all(|(_, name, default)| name.as_deref().is_none_or(|name| name == *default));
+    if is_default && schema.description.is_none() {
+        return None;
+    }
+
+    let operations: Vec<String> = roots
+        .iter()
+        .filter_map(|(operation, name, _)| {
+            name.as_ref()
+                .map(|name| format!("  {}: {}", operation, name))
+        })
+        .collect();
+    Some(format!(
+        "{}schema{}",
+        description_to_sdl(schema.description.as_deref(), ""),
+        block_to_sdl(&operations)
+    ))
+}
+
+fn directive_to_sdl(directive: &Directive) -> String {
+    let mut s = description_to_sdl(directive.description.as_deref(), "");
+    s.push_str(&format!(
+        "directive @{}{}",
+        directive.name.as_deref().unwrap_or_default(),
+        args_to_sdl(directive.args.as_deref().unwrap_or_default(), "")
+    ));
+    if directive.is_repeatable == Some(true) {
+        s.push_str(" repeatable");
+    }
+    let locations = directive.locations.as_deref().unwrap_or_default();
+    s.push_str(&format!(" on {}", locations.join(" | ")));
+    s
+}
+
+// Prints arguments on one line, or one per line when any of them has a
+// description.
+fn args_to_sdl(args: &[Input], indent: &str) -> String {
+    let args: Vec<&Input> = args.iter().filter(|arg| arg.input_type.is_some()).collect();
+    if args.is_empty() {
+        return String::new();
+    }
+
+    if args.iter().all(|arg| arg.description.is_none()) {
+        let args: Vec<String> = args.iter().map(|arg| input_value_to_sdl(arg, "")).collect();
+        return format!("({})", args.join(", "));
+    }
+
+    let inner = format!("{}  ", indent);
+    let args: Vec<String> = args
+        .iter()
+        .map(|arg| input_value_to_sdl(arg, &inner))
+        .collect();
+    format!("(\n{}\n{})", args.join("\n"), indent)
+}
+
+fn input_value_to_sdl(input: &Input, indent: &str) -> String {
+    let mut s = format!(
+        "{}{}{}: {}",
+        description_to_sdl(input.description.as_deref(), indent),
+        indent,
+        input.name.as_deref().unwrap_or_default(),
+        type_ref_to_sdl(&input.input_type)
+    );
+    if let Some(default_value) = &input.default_value {
+        s.push_str(&format!(" = {}", default_value));
+    }
+    s.push_str(&deprecated_to_sdl(
+        input.is_deprecated,
+        input.deprecation_reason.as_deref(),
+    ));
+    s
+}
+
+fn type_ref_to_sdl(type_ref: &Option<TypeRef>) -> String {
+    type_ref
+        .as_ref()
+        .map(TypeRef::decorated_name)
+        .unwrap_or_default()
+}
+
+fn block_to_sdl(lines: &[String]) -> String {
+    if lines.is_empty() {
+        String::new()
+    } else {
+        format!(" {{\n{}\n}}", lines.join("\n"))
+    }
+}
+
+fn deprecated_to_sdl(is_deprecated: Option<bool>, reason: Option<&str>) -> String {
+    match (is_deprecated, reason) {
+        (Some(true), Some(reason)) if reason != DEFAULT_DEPRECATION_REASON => {
+            format!(" @deprecated(reason: {})", to_string_value(reason))
+        }
+        (Some(true), _) => " @deprecated".to_string(),
+        _ => String::new(),
+    }
+}
+
+// Prints a description as a block string on its own line or lines.
+fn description_to_sdl(description: Option<&str>, indent: &str) -> String {
+    let description = match description {
+        Some(description) => description.replace("\"\"\"", "\\\"\"\""),
+        None => return String::new(),
+    };
+
+    if !description.contains('\n') && !description.ends_with('"') {
+        return format!("{}\"\"\"{}\"\"\"\n", indent, description);
+    }
+
+    let lines: String = description
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                "\n".to_string()
+            } else {
+                format!("{}{}\n", indent, line)
+            }
+        })
+        .collect();
+    format!("{}\"\"\"\n{}{}\"\"\"\n", indent, lines, indent)
+}
+
+fn to_string_value(value: &str) -> String {
+    // JSON string escapes are valid GraphQL string escapes.
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sdl;
+    use std::path::PathBuf;
+
+    fn parse(text: &str) -> Schema {
+        sdl::parse_files(&[(PathBuf::from("schema.graphql"), text.to_string())]).unwrap()
+    }
+
+    #[test]
+    fn test_schema_to_sdl_should_print_types() {
+        let text = r#"type Query {
+  team(id: ID!): Team
+  teams(first: Int = 10, after: String): [Team!]!
+}
+
+interface Node {
+  id: ID!
+}
+
+type Team implements Node {
+  id: ID!
+  name: String @deprecated(reason: "Use fullName")
+  fullName: String!
+  city: String @deprecated
+}
+
+union Member = Team | Player
+
+type Player
+
+enum Position {
+  GOALIE
+  DEFENSE @deprecated(reason: "Use \"D\"")
+}
+
+input TeamFilter @oneOf {
+  name: String
+  city: String = "Raleigh"
+}
+
+scalar Date @specifiedBy(url: "https://tools.ietf.org/html/rfc3339")
+"#;
+        assert_eq!(text, schema_to_sdl(&parse(text)));
+    }
+
+    #[test]
+    fn test_schema_to_sdl_should_print_descriptions() {
+        let text = r#""""A team"""
+type Team {
+  """
+  The team name
+
+  Unique within a league
+  """
+  name(
+    """The language to use"""
+    language: String
+    short: Boolean = false
+  ): String
+}
+"#;
+        assert_eq!(text, schema_to_sdl(&parse(text)));
+    }
+
+    #[test]
+    fn test_schema_to_sdl_should_print_directives() {
+        let text = r#""""Caches the field"""
+directive @cache(maxAge: Int!, scope: Scope = PUBLIC) repeatable on FIELD_DEFINITION | OBJECT
+
+type Query {
+  a: Int
+}
+
+enum Scope {
+  PUBLIC
+  PRIVATE
+}
+"#;
+        assert_eq!(text, schema_to_sdl(&parse(text)));
+    }
+
+    #[test]
+    fn test_schema_to_sdl_should_print_schema_when_roots_not_default() {
+        let text = r#"schema {
+  query: Root
+  mutation: Mutation
+}
+
+type Root {
+  a: Int
+}
+
+type Mutation {
+  b: Int
+}
+"#;
+        assert_eq!(text, schema_to_sdl(&parse(text)));
+    }
+
+    #[test]
+    fn test_schema_to_sdl_should_skip_default_schema_and_built_ins() {
+        let schema = Schema::from_str(
+            r#"{"data": {"__schema": {
+                "queryType": {"name": "Query"},
+                "types": [
+                    {"kind": "OBJECT", "name": "Query", "fields": [
+                        {"name": "a", "args": [], "type": {"kind": "SCALAR", "name": "String"}}
+                    ]},
+                    {"kind": "SCALAR", "name": "String"},
+                    {"kind": "OBJECT", "name": "__Type", "fields": []}
+                ],
+                "directives": [
+                    {"name": "skip", "locations": ["FIELD"], "args": []}
+                ]
+            }}}"#,
+        )
+        .unwrap();
+        assert_eq!("type Query {\n  a: String\n}\n", schema_to_sdl(&schema));
+    }
+
+    #[test]
+    fn test_type_to_sdl_should_skip_members_without_type() {
+        let schema = Schema::from_str(
+            r#"{"__schema": {"types": [
+                {"kind": "OBJECT", "name": "Query", "fields": [
+                    {"name": "a", "args": [{"name": "x"}, {"name": "y", "type": {"kind": "SCALAR", "name": "Int"}}], "type": {"kind": "SCALAR", "name": "Int"}},
+                    {"name": "b", "args": []}
+                ]},
+                {"kind": "INPUT_OBJECT", "name": "Filter", "inputFields": [{"name": "c"}]}
+            ]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            "type Query {\n  a(y: Int): Int\n}\n\ninput Filter\n",
+            schema_to_sdl(&schema)
+        );
+    }
+
+    #[test]
+    fn test_description_to_sdl_should_escape_triple_quotes() {
+        assert_eq!(
+            "\"\"\"Say \\\"\"\"hi\\\"\"\" \"\"\"\n",
+            description_to_sdl(Some("Say \"\"\"hi\"\"\" "), "")
+        );
+        assert_eq!(
+            "  \"\"\"\n  Ends with \"quote\"\n  \"\"\"\n",
+            description_to_sdl(Some("Ends with \"quote\""), "  ")
+        );
+    }
+}
@@ -0,0 +1,2280 @@
+use super::schema::{Directive, Enum, Field, Input, Schema, Type, TypeRef};
+use super::schema_sdl::{BUILT_IN_SCALARS, DEFAULT_DEPRECATION_REASON};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+pub fn parse_files(files: &[(PathBuf, String)]) -> Result<Schema, SdlError> {
+    let sources: Vec<(Option<&Path>, &str)> = files
+        .iter()
+        .map(|(path, text)| (Some(path.as_path()), text.as_str()))
+        .collect();
+    parse_sources(&sources)
+}
+
+fn parse_sources(sources: &[(Option<&Path>, &str)]) -> Result<Schema, SdlError> {
+    let mut documents = Vec::new();
+    for (index, (path, text)) in sources.iter().enumerate() {
+        let document = parse_document(text, index).map_err(|err| err.locate(*path, text))?;
+        documents.push(document);
+    }
+
+    merge_documents(documents)
+        .and_then(build_schema)
+        .map_err(|err| {
+            let (path, text) = sources[err.source];
+            err.locate(path, text)
+        })
+}
+
+fn parse_document(text: &str, source: usize) -> Result<Document, SdlError> {
+    let tokens = Lexer::new(text, source).tokenize()?;
+    Parser::new(tokens).parse_document()
+}
+
+// Finds the name of the first operation in a query document. Anonymous
+// operations, shorthand queries, and documents that don't lex have none.
+pub fn first_operation_name(text: &str) -> Option<String> {
+    let tokens = Lexer::new(text, 0).tokenize().ok()?;
+    let mut tokens = tokens.into_iter().map(|token| token.kind);
+    let mut depth = 0;
+    let mut in_fragment = false;
+    while let Some(kind) = tokens.next() {
+        match kind {
+            TokenKind::Punctuator("{") => {
+                if depth == 0 && !in_fragment {
+                    return None;
+                }
+                depth += 1;
+            }
+            TokenKind::Punctuator("}") => {
+                depth -= 1;
+                if depth == 0 {
+                    in_fragment = false;
+                }
+            }
+            TokenKind::Name(name) if depth == 0 && !in_fragment => match name.as_str() {
+                "query" | "mutation" | "subscription" => {
+                    return match tokens.next() {
+                        Some(TokenKind::Name(name)) => Some(name),
+                        _ => None,
+                    }
+                }
+                "fragment" => in_fragment = true,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
+// Errors
+
+#[derive(Debug)]
+pub struct SdlError {
+    pub message: String,
+    pub path: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+    length: usize,
+    source: usize,
+    source_line: String,
+}
+
+impl SdlError {
+    fn new(message: &str, pos: Pos) -> SdlError {
+        SdlError {
+            message: message.to_string(),
+            path: None,
+            line: pos.line,
+            column: pos.column,
+            length: pos.length,
+            source: pos.source,
+            source_line: String::new(),
+        }
+    }
+
+    fn locate(mut self, path: Option<&Path>, text: &str) -> SdlError {
+        self.path = path.map(|path| path.to_path_buf());
+        self.source_line = text
+            .split("\r\n")
+            .flat_map(|line| line.split(['\n', '\r']))
+            .nth(self.line - 1)
+            .unwrap_or("")
+            .to_string();
+        self
+    }
+}
+
+// Renders the error the way rustc does, with the offending source line and a
+// caret underline beneath the offending token:
+//
+// expected ":", found name "Int"
+//  --> schema.graphql:2:5
+//   |
+// 2 |   a Int
+//   |     ^^^
+impl fmt::Display for SdlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let path = match &self.path {
+            Some(path) => path.display().to_string(),
+            None => "<schema>".to_string(),
+        };
+        let number = self.line.to_string();
+        let gutter = " ".repeat(number.len());
+
+        writeln!(f, "{}", self.message)?;
+        write!(f, "{}--> {}:{}:{}", gutter, path, self.line, self.column)?;
+
+        if !self.source_line.is_empty() {
+            let line_length = self.source_line.chars().count();
+            let indent: String = self
+                .source_line
+                .chars()
+                .take(self.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let length = self
+                .length
+                .min(line_length.saturating_sub(self.column - 1))
+                .max(1);
+            writeln!(f)?;
+            writeln!(f, "{} |", gutter)?;
+            writeln!(f, "{} | {}", number, self.source_line)?;
+            write!(f, "{} | {}{}", gutter, indent, "^".repeat(length))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for SdlError {}
+
+// Lexer
+
+#[derive(Clone, Debug, PartialEq)]
+enum TokenKind {
+    Punctuator(&'static str),
+    Name(String),
+    Int(String),
+    Float(String),
+    String(String),
+    BlockString(String),
+    Eof,
+}
+
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenKind::Punctuator(p) => write!(f, "\"{}\"", p),
+            TokenKind::Name(name) => write!(f, "name \"{}\"", name),
+            TokenKind::Int(value) => write!(f, "int {}", value),
+            TokenKind::Float(value) => write!(f, "float {}", value),
+            TokenKind::String(_) | TokenKind::BlockString(_) => write!(f, "string"),
+            TokenKind::Eof => write!(f, "end of file"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Pos {
+    source: usize,
+    line: usize,
+    column: usize,
+    length: usize,
+}
+
+impl Pos {
+    fn at(line: usize, column: usize) -> Pos {
+        Pos {
+            source: 0,
+            line,
+            column,
+            length: 1,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Token {
+    kind: TokenKind,
+    pos: Pos,
+}
+
+fn error_at(message: &str, pos: Pos) -> SdlError {
+    SdlError::new(message, pos)
+}
+
+struct Lexer {
+    source: usize,
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Lexer {
+    fn new(text: &str, source: usize) -> Lexer {
+        Lexer {
+            source,
+            chars: text.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn tokenize(mut self) -> Result<Vec<Token>, SdlError> {
+        let mut tokens = Vec::new();
+        loop {
+            let token = self.next_token()?;
+            let done = token.kind == TokenKind::Eof;
+            tokens.push(token);
+            if done {
+                return Ok(tokens);
+            }
+        }
+    }
+
+    fn peek(&self, offset: usize) -> Option<char> {
+        self.chars.get(self.pos + offset).copied()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek(0)?;
+        self.pos += 1;
+        match c {
+            '\n' => {
+                self.line += 1;
+                self.column = 1;
+            }
+            '\r' => {
+                if self.peek(0) != Some('\n') {
+                    self.line += 1;
+                    self.column = 1;
+                }
+            }
+            _ => self.column += 1,
+        }
+        Some(c)
+    }
+
+    fn skip_ignored(&mut self) {
+        while let Some(c) = self.peek(0) {
+            match c {
+                ' ' | '\t' | '\n' | '\r' | ',' | '\u{feff}' => {
+                    self.advance();
+                }
+                '#' => {
+                    while let Some(c) = self.peek(0) {
+                        if c == '\n' || c == '\r' {
+                            break;
+                        }
+                        self.advance();
+                    }
+                }
+                _ => break,
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Token, SdlError> {
+        self.skip_ignored();
+
+        let start = self.pos;
+        let line = self.line;
+        let column = self.column;
+
+        let kind = match self.peek(0) {
+            Some(c) => self.read_token(c, Pos::at(line, column))?,
+            None => TokenKind::Eof,
+        };
+
+        Ok(Token {
+            kind,
+            pos: Pos {
+                source: self.source,
+                line,
+                column,
+                length: (self.pos - start).max(1),
+            },
+        })
+    }
+
+    fn read_token(&mut self, c: char, pos: Pos) -> Result<TokenKind, SdlError> {
+        match c {
+            '!' | '$' | '&' | '(' | ')' | ':' | '=' | '@' | '[' | ']' | '{' | '|' | '}' => {
+                self.advance();
+                Ok(TokenKind::Punctuator(punctuator(c)))
+            }
+            '.' => {
+                if self.peek(1) == Some('.') && self.peek(2) == Some('.') {
+                    self.advance();
+                    self.advance();
+                    self.advance();
+                    Ok(TokenKind::Punctuator("..."))
+                } else {
+                    Err(error_at("unexpected character \".\"", pos))
+                }
+            }
+            '"' => {
+                if self.peek(1) == Some('"') && self.peek(2) == Some('"') {
+                    Ok(TokenKind::BlockString(self.read_block_string()?))
+                } else {
+                    Ok(TokenKind::String(self.read_string()?))
+                }
+            }
+            '-' | '0'..='9' => self.read_number(),
+            c if is_name_start(c) => {
+                let mut name = String::new();
+                while let Some(c) = self.peek(0) {
+                    if !is_name_continue(c) {
+                        break;
+                    }
+                    name.push(c);
+                    self.advance();
+                }
+                Ok(TokenKind::Name(name))
+            }
+            c => Err(error_at(&format!("unexpected character {:?}", c), pos)),
+        }
+    }
+
+    fn read_digits(&mut self, s: &mut String) -> Result<(), SdlError> {
+        match self.peek(0) {
+            Some(c) if c.is_ascii_digit() => {}
+            _ => return Err(error_at("expected digit", Pos::at(self.line, self.column))),
+        }
+        while let Some(c) = self.peek(0) {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            s.push(c);
+            self.advance();
+        }
+        Ok(())
+    }
+
+    fn read_number(&mut self) -> Result<TokenKind, SdlError> {
+        let mut s = String::new();
+        let mut is_float = false;
+
+        if self.peek(0) == Some('-') {
+            s.push('-');
+            self.advance();
+        }
+
+        self.read_digits(&mut s)?;
+
+        if self.peek(0) == Some('.') {
+            is_float = true;
+            s.push('.');
+            self.advance();
+            self.read_digits(&mut s)?;
+        }
+
+        if let Some(c @ 'e') | Some(c @ 'E') = self.peek(0) {
+            is_float = true;
+            s.push(c);
+            self.advance();
+            if let Some(c @ '+') | Some(c @ '-') = self.peek(0) {
+                s.push(c);
+                self.advance();
+            }
+            self.read_digits(&mut s)?;
+        }
+
+        if is_float {
+            Ok(TokenKind::Float(s))
+        } else {
+            Ok(TokenKind::Int(s))
+        }
+    }
+
+    fn read_string(&mut self) -> Result<String, SdlError> {
+        let line = self.line;
+        let column = self.column;
+        let mut s = String::new();
+
+        self.advance();
+        loop {
+            match self.advance() {
+                None | Some('\n') | Some('\r') => {
+                    return Err(error_at("unterminated string", Pos::at(line, column)))
+                }
+                Some('"') => return Ok(s),
+                Some('\\') => {
+                    let escape_line = self.line;
+                    let escape_column = self.column - 1;
+                    match self.advance() {
+                        Some('"') => s.push('"'),
+                        Some('\\') => s.push('\\'),
+                        Some('/') => s.push('/'),
+                        Some('b') => s.push('\u{8}'),
+                        Some('f') => s.push('\u{c}'),
+                        Some('n') => s.push('\n'),
+                        Some('r') => s.push('\r'),
+                        Some('t') => s.push('\t'),
+                        Some('u') => {
+                            let hex: String = (0..4).filter_map(|_| self.advance()).collect();
+                            match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                                Some(c) => s.push(c),
+                                None => {
+                                    return Err(error_at(
+                                        &format!("invalid unicode escape \"\\u{}\"", hex),
+                                        Pos::at(escape_line, escape_column),
+                                    ))
+                                }
+                            }
+                        }
+                        _ => {
+                            return Err(error_at(
+                                "invalid escape sequence",
+                                Pos::at(escape_line, escape_column),
+                            ))
+                        }
+                    }
+                }
+                Some(c) => s.push(c),
+            }
+        }
+    }
+
+    fn read_block_string(&mut self) -> Result<String, SdlError> {
+        let line = self.line;
+        let column = self.column;
+        let mut raw = String::new();
+
+        self.advance();
+        self.advance();
+        self.advance();
+        loop {
+            match self.peek(0) {
+                None => return Err(error_at("unterminated block string", Pos::at(line, column))),
+                Some('"') if self.peek(1) == Some('"') && self.peek(2) == Some('"') => {
+                    self.advance();
+                    self.advance();
+                    self.advance();
+                    return Ok(block_string_value(&raw));
+                }
+                Some('\\')
+                    if self.peek(1) == Some('"')
+                        && self.peek(2) == Some('"')
+                        && self.peek(3) == Some('"') =>
+                {
+                    raw.push_str("\"\"\"");
+                    for _ in 0..4 {
+                        self.advance();
+                    }
+                }
+                Some(c) => {
+                    raw.push(c);
+                    self.advance();
+                }
+            }
+        }
+    }
+}
+
+fn punctuator(c: char) -> &'static str {
+    match c {
+        '!' => "!",
+        '$' => "$",
+        '&' => "&",
+        '(' => "(",
+        ')' => ")",
+        ':' => ":",
+        '=' => "=",
+        '@' => "@",
+        '[' => "[",
+        ']' => "]",
+        '{' => "{",
+        '|' => "|",
+        _ => "}",
+    }
+}
+
+fn is_name_start(c: char) -> bool {
+    c == '_' || c.is_ascii_alphabetic()
+}
+
+fn is_name_continue(c: char) -> bool {
+    c == '_' || c.is_ascii_alphanumeric()
+}
+
+// Implements the BlockStringValue() algorithm from the GraphQL spec: strips
+// the common indentation and any leading and trailing blank lines.
+fn block_string_value(raw: &str) -> String {
+    let lines: Vec<&str> = raw
+        .split("\r\n")
+        .flat_map(|line| line.split(['\n', '\r']))
+        .collect();
+
+    let common_indent = lines
+        .iter()
+        .skip(1)
+        .filter_map(|line| {
+            let indent = line.chars().take_while(|c| *c == ' ' || *c == '\t').count();
+            if indent < line.chars().count() {
+                Some(indent)
+            } else {
+                None
+            }
+        })
+        .min()
+        .unwrap_or(0);
+
+    let mut lines: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 {
+                line.to_string()
+            } else {
+                line.chars().skip(common_indent).collect()
+            }
+        })
+        .collect();
+
+    let is_blank = |line: &String| line.chars().all(|c| c == ' ' || c == '\t');
+    while lines.first().is_some_and(is_blank) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(is_blank) {
+        lines.pop();
+    }
+
+    lines.join("\n")
+}
+
+// Syntax tree
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Int(String),
+    Float(String),
+    String(String),
+    Boolean(bool),
+    Null,
+    Enum(String),
+    List(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+// Prints values the way graphql-js does in an introspection `defaultValue`.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(value) | Value::Float(value) | Value::Enum(value) => write!(f, "{}", value),
+            Value::String(value) => write!(f, "{}", serde_json::Value::from(value.as_str())),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Null => write!(f, "null"),
+            Value::List(values) => {
+                let values: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+            Value::Object(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(name, value)| format!("{}: {}", name, value))
+                    .collect();
+                write!(f, "{{{}}}", fields.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AppliedDirective {
+    name: String,
+    args: Vec<(String, Value)>,
+}
+
+fn specified_by_of(directives: &[AppliedDirective]) -> Option<String> {
+    directives
+        .iter()
+        .find(|directive| directive.name == "specifiedBy")
+        .and_then(|directive| directive.args.iter().find(|(name, _)| name == "url"))
+        .and_then(|(_, value)| match value {
+            Value::String(url) => Some(url.clone()),
+            _ => None,
+        })
+}
+
+fn deprecation_of(directives: &[AppliedDirective]) -> Option<String> {
+    directives
+        .iter()
+        .find(|directive| directive.name == "deprecated")
+        .map(|directive| {
+            match directive
+                .args
+                .iter()
+                .find(|(name, _)| name == "reason")
+                .map(|(_, value)| value)
+            {
+                Some(Value::String(reason)) => reason.clone(),
+                _ => DEFAULT_DEPRECATION_REASON.to_string(),
+            }
+        })
+}
+
+#[derive(Clone, Debug)]
+struct Name {
+    value: String,
+    pos: Pos,
+}
+
+#[derive(Clone, Debug)]
+enum TypeNode {
+    Named(Name),
+    List(Box<TypeNode>),
+    NonNull(Box<TypeNode>),
+}
+
+#[derive(Clone, Debug)]
+struct InputValueDefinition {
+    name: String,
+    pos: Pos,
+    description: Option<String>,
+    typ: TypeNode,
+    default_value: Option<Value>,
+    directives: Vec<AppliedDirective>,
+}
+
+#[derive(Clone, Debug)]
+struct FieldDefinition {
+    name: String,
+    pos: Pos,
+    description: Option<String>,
+    args: Vec<InputValueDefinition>,
+    typ: TypeNode,
+    directives: Vec<AppliedDirective>,
+}
+
+#[derive(Clone, Debug)]
+struct EnumValueDefinition {
+    name: String,
+    pos: Pos,
+    description: Option<String>,
+    directives: Vec<AppliedDirective>,
+}
+
+#[derive(Clone, Debug)]
+struct TypeDefinition {
+    kind: &'static str,
+    name: String,
+    description: Option<String>,
+    interfaces: Vec<Name>,
+    fields: Vec<FieldDefinition>,
+    inputs: Vec<InputValueDefinition>,
+    values: Vec<EnumValueDefinition>,
+    members: Vec<Name>,
+    directives: Vec<AppliedDirective>,
+    is_extension: bool,
+    pos: Pos,
+}
+
+impl TypeDefinition {
+    fn new(kind: &'static str, name: Name, description: Option<String>) -> Self {
+        TypeDefinition {
+            kind,
+            name: name.value,
+            description,
+            interfaces: Vec::new(),
+            fields: Vec::new(),
+            inputs: Vec::new(),
+            values: Vec::new(),
+            members: Vec::new(),
+            directives: Vec::new(),
+            is_extension: false,
+            pos: name.pos,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct DirectiveDefinition {
+    name: String,
+    pos: Pos,
+    description: Option<String>,
+    args: Vec<InputValueDefinition>,
+    is_repeatable: bool,
+    locations: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+struct SchemaDefinition {
+    description: Option<String>,
+    operations: Vec<(Name, Name)>,
+    is_extension: bool,
+    pos: Pos,
+}
+
+impl SchemaDefinition {
+    fn root_type(&self, operation: &str) -> Option<String> {
+        self.operations
+            .iter()
+            .find(|(op, _)| op.value == operation)
+            .map(|(_, typ)| typ.value.clone())
+    }
+}
+
+#[derive(Debug, Default)]
+struct Document {
+    schema_definitions: Vec<SchemaDefinition>,
+    types: Vec<TypeDefinition>,
+    directives: Vec<DirectiveDefinition>,
+}
+
+// Parser
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(tokens: Vec<Token>) -> Parser {
+        Parser { tokens, pos: 0 }
+    }
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos]
+    }
+
+    fn peek_kind(&self) -> &TokenKind {
+        &self.peek().kind
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.pos].clone();
+        if token.kind != TokenKind::Eof {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn unexpected(&self, expected: &str) -> SdlError {
+        let token = self.peek();
+        error_at(
+            &format!("expected {}, found {}", expected, token.kind),
+            token.pos,
+        )
+    }
+
+    fn is_punctuator(&self, p: &str) -> bool {
+        matches!(self.peek_kind(), TokenKind::Punctuator(q) if *q == p)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek_kind(), TokenKind::Name(name) if name == keyword)
+    }
+
+    fn skip_punctuator(&mut self, p: &str) -> bool {
+        if self.is_punctuator(p) {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punctuator(&mut self, p: &str) -> Result<Token, SdlError> {
+        if self.is_punctuator(p) {
+            Ok(self.next())
+        } else {
+            Err(self.unexpected(&format!("\"{}\"", p)))
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<Token, SdlError> {
+        if self.is_keyword(keyword) {
+            Ok(self.next())
+        } else {
+            Err(self.unexpected(&format!("\"{}\"", keyword)))
+        }
+    }
+
+    fn expect_name(&mut self) -> Result<String, SdlError> {
+        match self.peek_kind() {
+            TokenKind::Name(name) => {
+                let name = name.clone();
+                self.next();
+                Ok(name)
+            }
+            _ => Err(self.unexpected("name")),
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<Name, SdlError> {
+        let pos = self.peek().pos;
+        Ok(Name {
+            value: self.expect_name()?,
+            pos,
+        })
+    }
+
+    fn parse_document(&mut self) -> Result<Document, SdlError> {
+        let mut document = Document::default();
+
+        while *self.peek_kind() != TokenKind::Eof {
+            self.parse_definition(&mut document)?;
+        }
+
+        Ok(document)
+    }
+
+    fn parse_description(&mut self) -> Option<String> {
+        match self.peek_kind() {
+            TokenKind::String(s) | TokenKind::BlockString(s) => {
+                let s = s.clone();
+                self.next();
+                Some(s)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_definition(&mut self, document: &mut Document) -> Result<(), SdlError> {
+        let description = self.parse_description();
+
+        let is_extension = self.is_keyword("extend");
+        if is_extension {
+            if description.is_some() {
+                return Err(error_at(
+                    "extensions cannot have descriptions",
+                    self.peek().pos,
+                ));
+            }
+            self.next();
+        }
+
+        let keyword = match self.peek_kind() {
+            TokenKind::Name(name) => name.clone(),
+            _ => return Err(self.unexpected("definition")),
+        };
+
+        let definition = match keyword.as_str() {
+            "schema" => {
+                let definition = self.parse_schema_definition(description, is_extension)?;
+                document.schema_definitions.push(definition);
+                return Ok(());
+            }
+            "directive" if !is_extension => {
+                let definition = self.parse_directive_definition(description)?;
+                document.directives.push(definition);
+                return Ok(());
+            }
+            "scalar" => self.parse_scalar_definition(description)?,
+            "type" | "interface" => self.parse_object_definition(description)?,
+            "union" => self.parse_union_definition(description)?,
+            "enum" => self.parse_enum_definition(description)?,
+            "input" => self.parse_input_definition(description)?,
+            _ => return Err(self.unexpected("definition")),
+        };
+
+        document.types.push(TypeDefinition {
+            is_extension,
+            ..definition
+        });
+        Ok(())
+    }
+
+    fn parse_schema_definition(
+        &mut self,
+        description: Option<String>,
+        is_extension: bool,
+    ) -> Result<SchemaDefinition, SdlError> {
+        let pos = self.expect_keyword("schema")?.pos;
+        self.parse_directives()?;
+        let mut operations = Vec::new();
+        if !is_extension || self.is_punctuator("{") {
+            self.expect_punctuator("{")?;
+            while !self.skip_punctuator("}") {
+                let operation = self.parse_name()?;
+                self.expect_punctuator(":")?;
+                let typ = self.parse_name()?;
+                match operation.value.as_str() {
+                    "query" | "mutation" | "subscription" => operations.push((operation, typ)),
+                    _ => {
+                        return Err(error_at(
+                            &format!("unknown operation type \"{}\"", operation.value),
+                            operation.pos,
+                        ))
+                    }
+                }
+            }
+        }
+        Ok(SchemaDefinition {
+            description,
+            operations,
+            is_extension,
+            pos,
+        })
+    }
+
+    fn parse_scalar_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<TypeDefinition, SdlError> {
+        self.expect_keyword("scalar")?;
+        let name = self.parse_name()?;
+        let mut definition = TypeDefinition::new("SCALAR", name, description);
+        definition.directives = self.parse_directives()?;
+        Ok(definition)
+    }
+
+    fn parse_object_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<TypeDefinition, SdlError> {
+        let token = self.next();
+        let kind = if token.kind == TokenKind::Name("type".to_string()) {
+            "OBJECT"
+        } else {
+            "INTERFACE"
+        };
+        let name = self.parse_name()?;
+        let mut definition = TypeDefinition::new(kind, name, description);
+        definition.interfaces = self.parse_implements_interfaces()?;
+        definition.directives = self.parse_directives()?;
+        if self.is_punctuator("{") {
+            definition.fields = self.parse_fields_definition()?;
+        }
+        Ok(definition)
+    }
+
+    fn parse_implements_interfaces(&mut self) -> Result<Vec<Name>, SdlError> {
+        let mut interfaces = Vec::new();
+        if self.is_keyword("implements") {
+            self.next();
+            self.skip_punctuator("&");
+            interfaces.push(self.parse_name()?);
+            while self.skip_punctuator("&") {
+                interfaces.push(self.parse_name()?);
+            }
+        }
+        Ok(interfaces)
+    }
+
+    fn parse_fields_definition(&mut self) -> Result<Vec<FieldDefinition>, SdlError> {
+        let mut fields = Vec::new();
+        self.expect_punctuator("{")?;
+        while !self.skip_punctuator("}") {
+            let description = self.parse_description();
+            let Name { value: name, pos } = self.parse_name()?;
+            let args = self.parse_arguments_definition()?;
+            self.expect_punctuator(":")?;
+            let typ = self.parse_type()?;
+            let directives = self.parse_directives()?;
+            fields.push(FieldDefinition {
+                name,
+                pos,
+                description,
+                args,
+                typ,
+                directives,
+            });
+        }
+        Ok(fields)
+    }
+
+    fn parse_arguments_definition(&mut self) -> Result<Vec<InputValueDefinition>, SdlError> {
+        let mut args = Vec::new();
+        if self.skip_punctuator("(") {
+            while !self.skip_punctuator(")") {
+                args.push(self.parse_input_value_definition()?);
+            }
+        }
+        Ok(args)
+    }
+
+    fn parse_input_value_definition(&mut self) -> Result<InputValueDefinition, SdlError> {
+        let description = self.parse_description();
+        let Name { value: name, pos } = self.parse_name()?;
+        self.expect_punctuator(":")?;
+        let typ = self.parse_type()?;
+        let default_value = if self.skip_punctuator("=") {
+            Some(self.parse_value()?)
+        } else {
+            None
+        };
+        let directives = self.parse_directives()?;
+        Ok(InputValueDefinition {
+            name,
+            pos,
+            description,
+            typ,
+            default_value,
+            directives,
+        })
+    }
+
+    fn parse_union_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<TypeDefinition, SdlError> {
+        self.expect_keyword("union")?;
+        let name = self.parse_name()?;
+        let mut definition = TypeDefinition::new("UNION", name, description);
+        definition.directives = self.parse_directives()?;
+        if self.skip_punctuator("=") {
+            self.skip_punctuator("|");
+            definition.members.push(self.parse_name()?);
+            while self.skip_punctuator("|") {
+                definition.members.push(self.parse_name()?);
+            }
+        }
+        Ok(definition)
+    }
+
+    fn parse_enum_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<TypeDefinition, SdlError> {
+        self.expect_keyword("enum")?;
+        let name = self.parse_name()?;
+        let mut definition = TypeDefinition::new("ENUM", name, description);
+        definition.directives = self.parse_directives()?;
+        if self.skip_punctuator("{") {
+            while !self.skip_punctuator("}") {
+                let description = self.parse_description();
+                let Name { value: name, pos } = self.parse_name()?;
+                let directives = self.parse_directives()?;
+                definition.values.push(EnumValueDefinition {
+                    name,
+                    pos,
+                    description,
+                    directives,
+                });
+            }
+        }
+        Ok(definition)
+    }
+
+    fn parse_input_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<TypeDefinition, SdlError> {
+        self.expect_keyword("input")?;
+        let name = self.parse_name()?;
+        let mut definition = TypeDefinition::new("INPUT_OBJECT", name, description);
+        definition.directives = self.parse_directives()?;
+        if self.skip_punctuator("{") {
+            while !self.skip_punctuator("}") {
+                definition.inputs.push(self.parse_input_value_definition()?);
+            }
+        }
+        Ok(definition)
+    }
+
+    fn parse_directive_definition(
+        &mut self,
+        description: Option<String>,
+    ) -> Result<DirectiveDefinition, SdlError> {
+        self.expect_keyword("directive")?;
+        self.expect_punctuator("@")?;
+        let Name { value: name, pos } = self.parse_name()?;
+        let args = self.parse_arguments_definition()?;
+        let is_repeatable = self.is_keyword("repeatable");
+        if is_repeatable {
+            self.next();
+        }
+        self.expect_keyword("on")?;
+        self.skip_punctuator("|");
+        let mut locations = vec![self.expect_name()?];
+        while self.skip_punctuator("|") {
+            locations.push(self.expect_name()?);
+        }
+        Ok(DirectiveDefinition {
+            name,
+            pos,
+            description,
+            args,
+            is_repeatable,
+            locations,
+        })
+    }
+
+    fn parse_directives(&mut self) -> Result<Vec<AppliedDirective>, SdlError> {
+        let mut directives = Vec::new();
+        while self.skip_punctuator("@") {
+            let name = self.expect_name()?;
+            let mut args = Vec::new();
+            if self.skip_punctuator("(") {
+                while !self.skip_punctuator(")") {
+                    let arg_name = self.expect_name()?;
+                    self.expect_punctuator(":")?;
+                    args.push((arg_name, self.parse_value()?));
+                }
+            }
+            directives.push(AppliedDirective { name, args });
+        }
+        Ok(directives)
+    }
+
+    fn parse_type(&mut self) -> Result<TypeNode, SdlError> {
+        let typ = if self.skip_punctuator("[") {
+            let inner = self.parse_type()?;
+            self.expect_punctuator("]")?;
+            TypeNode::List(Box::new(inner))
+        } else {
+            TypeNode::Named(self.parse_name()?)
+        };
+
+        if self.skip_punctuator("!") {
+            Ok(TypeNode::NonNull(Box::new(typ)))
+        } else {
+            Ok(typ)
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, SdlError> {
+        let token = self.peek().clone();
+        match token.kind {
+            TokenKind::Punctuator("[") => {
+                self.next();
+                let mut values = Vec::new();
+                while !self.skip_punctuator("]") {
+                    values.push(self.parse_value()?);
+                }
+                Ok(Value::List(values))
+            }
+            TokenKind::Punctuator("{") => {
+                self.next();
+                let mut fields = Vec::new();
+                while !self.skip_punctuator("}") {
+                    let name = self.expect_name()?;
+                    self.expect_punctuator(":")?;
+                    fields.push((name, self.parse_value()?));
+                }
+                Ok(Value::Object(fields))
+            }
+            TokenKind::Int(value) => {
+                self.next();
+                Ok(Value::Int(value))
+            }
+            TokenKind::Float(value) => {
+                self.next();
+                Ok(Value::Float(value))
+            }
+            TokenKind::String(value) | TokenKind::BlockString(value) => {
+                self.next();
+                Ok(Value::String(value))
+            }
+            TokenKind::Name(name) => {
+                self.next();
+                Ok(match name.as_str() {
+                    "true" => Value::Boolean(true),
+                    "false" => Value::Boolean(false),
+                    "null" => Value::Null,
+                    _ => Value::Enum(name),
+                })
+            }
+            _ => Err(self.unexpected("value")),
+        }
+    }
+}
+
+// Merging
+
+fn kind_description(kind: &str) -> &'static str {
+    match kind {
+        "OBJECT" => "an object type",
+        "INTERFACE" => "an interface",
+        "UNION" => "a union",
+        "ENUM" => "an enum",
+        "INPUT_OBJECT" => "an input type",
+        _ => "a scalar",
+    }
+}
+
+// Appends `additions` to `existing`, failing on the first item whose name is
+// already present.
+fn extend_unique<T>(
+    existing: &mut Vec<T>,
+    additions: Vec<T>,
+    name_of: impl Fn(&T) -> (&str, Pos),
+    describe: impl Fn(&str) -> String,
+) -> Result<(), SdlError> {
+    for addition in additions {
+        let (name, pos) = name_of(&addition);
+        if existing.iter().any(|item| name_of(item).0 == name) {
+            return Err(error_at(&describe(name), pos));
+        }
+        existing.push(addition);
+    }
+    Ok(())
+}
+
+// Adds the members of `extension` to `base`, which may be an empty definition
+// when checking a type's own definition for duplicate members.
+fn apply_extension(base: &mut TypeDefinition, extension: TypeDefinition) -> Result<(), SdlError> {
+    let type_name = base.name.clone();
+    base.directives.extend(extension.directives);
+    extend_unique(
+        &mut base.interfaces,
+        extension.interfaces,
+        |name| (&name.value, name.pos),
+        |name| format!("type \"{}\" already implements \"{}\"", type_name, name),
+    )?;
+    extend_unique(
+        &mut base.fields,
+        extension.fields,
+        |field| (&field.name, field.pos),
+        |name| format!("field \"{}.{}\" is already defined", type_name, name),
+    )?;
+    extend_unique(
+        &mut base.inputs,
+        extension.inputs,
+        |input| (&input.name, input.pos),
+        |name| format!("input field \"{}.{}\" is already defined", type_name, name),
+    )?;
+    extend_unique(
+        &mut base.values,
+        extension.values,
+        |value| (&value.name, value.pos),
+        |name| format!("enum value \"{}.{}\" is already defined", type_name, name),
+    )?;
+    extend_unique(
+        &mut base.members,
+        extension.members,
+        |name| (&name.value, name.pos),
+        |name| format!("union \"{}\" already includes \"{}\"", type_name, name),
+    )
+}
+
+fn merge_documents(documents: Vec<Document>) -> Result<Document, SdlError> {
+    let mut merged = Document::default();
+    let mut schema_extensions = Vec::new();
+    let mut type_extensions = Vec::new();
+
+    for document in documents {
+        for definition in document.schema_definitions {
+            if definition.is_extension {
+                schema_extensions.push(definition);
+            } else if merged.schema_definitions.is_empty() {
+                merged.schema_definitions.push(SchemaDefinition {
+                    operations: Vec::new(),
+                    ..definition.clone()
+                });
+                schema_extensions.insert(0, definition);
+            } else {
+                return Err(error_at("schema is defined more than once", definition.pos));
+            }
+        }
+
+        for definition in document.types {
+            if definition.is_extension {
+                type_extensions.push(definition);
+            } else if merged.types.iter().any(|typ| typ.name == definition.name) {
+                return Err(error_at(
+                    &format!("type \"{}\" is defined more than once", definition.name),
+                    definition.pos,
+                ));
+            } else {
+                let mut base = TypeDefinition {
+                    interfaces: Vec::new(),
+                    fields: Vec::new(),
+                    inputs: Vec::new(),
+                    values: Vec::new(),
+                    members: Vec::new(),
+                    directives: Vec::new(),
+                    ..definition.clone()
+                };
+                apply_extension(&mut base, definition)?;
+                merged.types.push(base);
+            }
+        }
+
+        for definition in document.directives {
+            if merged
+                .directives
+                .iter()
+                .any(|directive| directive.name == definition.name)
+            {
+                return Err(error_at(
+                    &format!(
+                        "directive \"@{}\" is defined more than once",
+                        definition.name
+                    ),
+                    definition.pos,
+                ));
+            }
+            merged.directives.push(definition);
+        }
+    }
+
+    for extension in schema_extensions {
+        if merged.schema_definitions.is_empty() {
+            merged.schema_definitions.push(SchemaDefinition {
+                operations: Vec::new(),
+                ..extension.clone()
+            });
+        }
+        extend_unique(
+            &mut merged.schema_definitions[0].operations,
+            extension.operations,
+            |(operation, _)| (&operation.value, operation.pos),
+            |name| format!("schema already defines a {} type", name),
+        )?;
+    }
+
+    for extension in type_extensions {
+        let base = match merged
+            .types
+            .iter_mut()
+            .find(|typ| typ.name == extension.name)
+        {
+            Some(base) => base,
+            None => {
+                return Err(error_at(
+                    &format!("cannot extend undefined type \"{}\"", extension.name),
+                    extension.pos,
+                ))
+            }
+        };
+        if base.kind != extension.kind {
+            return Err(error_at(
+                &format!(
+                    "cannot extend \"{}\": it is {}, not {}",
+                    extension.name,
+                    kind_description(base.kind),
+                    kind_description(extension.kind)
+                ),
+                extension.pos,
+            ));
+        }
+        apply_extension(base, extension)?;
+    }
+
+    Ok(merged)
+}
+
+// Schema builder
+
+fn build_schema(document: Document) -> Result<Schema, SdlError> {
+    let kinds: HashMap<String, &'static str> = document
+        .types
+        .iter()
+        .map(|definition| (definition.name.clone(), definition.kind))
+        .collect();
+
+    let mut builder = Builder {
+        kinds,
+        used_scalars: Vec::new(),
+    };
+
+    let mut types = Vec::new();
+    for definition in document.types.iter() {
+        types.push(builder.build_type(definition, &document.types)?);
+    }
+
+    for name in BUILT_IN_SCALARS.iter() {
+        if builder.used_scalars.iter().any(|used| used == name)
+            && !builder.kinds.contains_key(*name)
+        {
+            types.push(Type {
+                name: Some(name.to_string()),
+                kind: Some("SCALAR".to_string()),
+                ..Default::default()
+            });
+        }
+    }
+
+    let mut directives = Vec::new();
+    for definition in document.directives.iter() {
+        directives.push(Directive {
+            name: Some(definition.name.clone()),
+            description: definition.description.clone(),
+            locations: Some(definition.locations.clone()),
+            args: Some(builder.build_inputs(&definition.args)?),
+            is_repeatable: Some(definition.is_repeatable),
+        });
+    }
+
+    let schema_definition = document.schema_definitions.first();
+    let root_type = |operation: &str, default: &str| {
+        schema_definition
+            .and_then(|definition| definition.root_type(operation))
+            .or_else(|| {
+                if builder.kinds.contains_key(default) {
+                    Some(default.to_string())
+                } else {
+                    None
+                }
+            })
+            .map(|name| Type {
+                name: Some(name),
+                ..Default::default()
+            })
+    };
+
+    Ok(Schema {
+        description: schema_definition.and_then(|definition| definition.description.clone()),
+        query_type: root_type("query", "Query"),
+        mutation_type: root_type("mutation", "Mutation"),
+        subscription_type: root_type("subscription", "Subscription"),
+        types: Some(types),
+        directives: Some(directives),
+    })
+}
+
+struct Builder {
+    kinds: HashMap<String, &'static str>,
+    used_scalars: Vec<String>,
+}
+
+impl Builder {
+    fn build_type(
+        &mut self,
+        definition: &TypeDefinition,
+        all: &[TypeDefinition],
+    ) -> Result<Type, SdlError> {
+        let kind = definition.kind;
+        let has_fields = kind == "OBJECT" || kind == "INTERFACE";
+
+        let fields = if has_fields {
+            let mut fields = Vec::new();
+            for field in definition.fields.iter() {
+                fields.push(self.build_field(field)?);
+            }
+            Some(fields)
+        } else {
+            None
+        };
+
+        let interfaces = if has_fields {
+            Some(self.build_named_refs(&definition.interfaces)?)
+        } else {
+            None
+        };
+
+        let inputs = if kind == "INPUT_OBJECT" {
+            Some(self.build_inputs(&definition.inputs)?)
+        } else {
+            None
+        };
+
+        let enums = if kind == "ENUM" {
+            Some(
+                definition
+                    .values
+                    .iter()
+                    .map(|value| {
+                        let deprecation = deprecation_of(&value.directives);
+                        Enum {
+                            name: Some(value.name.clone()),
+                            description: value.description.clone(),
+                            is_deprecated: Some(deprecation.is_some()),
+                            deprecation_reason: deprecation,
+                        }
+                    })
+                    .collect(),
+            )
+        } else {
+            None
+        };
+
+        let possible_types = match kind {
+            "UNION" => Some(self.build_named_refs(&definition.members)?),
+            "INTERFACE" => {
+                let implementors: Vec<Name> = all
+                    .iter()
+                    .filter(|other| {
+                        other.kind == "OBJECT"
+                            && other
+                                .interfaces
+                                .iter()
+                                .any(|interface| interface.value == definition.name)
+                    })
+                    .map(|other| Name {
+                        value: other.name.clone(),
+                        pos: other.pos,
+                    })
+                    .collect();
+                Some(self.build_named_refs(&implementors)?)
+            }
+            _ => None,
+        };
+
+        Ok(Type {
+            name: Some(definition.name.clone()),
+            kind: Some(kind.to_string()),
+            description: definition.description.clone(),
+            fields,
+            inputs,
+            interfaces,
+            enums,
+            possible_types,
+            specified_by_url: specified_by_of(&definition.directives),
+            is_one_of: if kind == "INPUT_OBJECT" {
+                Some(
+                    definition
+                        .directives
+                        .iter()
+                        .any(|directive| directive.name == "oneOf"),
+                )
+            } else {
+                None
+            },
+        })
+    }
+
+    fn build_field(&mut self, field: &FieldDefinition) -> Result<Field, SdlError> {
+        let deprecation = deprecation_of(&field.directives);
+        Ok(Field {
+            name: Some(field.name.clone()),
+            description: field.description.clone(),
+            args: Some(self.build_inputs(&field.args)?),
+            field_type: Some(self.build_type_ref(&field.typ)?),
+            is_deprecated: Some(deprecation.is_some()),
+            deprecation_reason: deprecation,
+        })
+    }
+
+    fn build_inputs(&mut self, inputs: &[InputValueDefinition]) -> Result<Vec<Input>, SdlError> {
+        let mut built = Vec::new();
+        for input in inputs.iter() {
+            let deprecation = deprecation_of(&input.directives);
+            built.push(Input {
+                name: Some(input.name.clone()),
+                description: input.description.clone(),
+                input_type: Some(self.build_type_ref(&input.typ)?),
+                default_value: input.default_value.as_ref().map(|value| value.to_string()),
+                is_deprecated: Some(deprecation.is_some()),
+                deprecation_reason: deprecation,
+            });
+        }
+        Ok(built)
+    }
+
+    fn build_named_refs(&self, names: &[Name]) -> Result<Vec<TypeRef>, SdlError> {
+        let mut refs = Vec::new();
+        for name in names.iter() {
+            match self.kinds.get(&name.value) {
+                Some(kind) => refs.push(TypeRef {
+                    name: Some(name.value.clone()),
+                    kind: Some(kind.to_string()),
+                    of_type: None,
+                }),
+                None => {
+                    return Err(error_at(
+                        &format!("unknown type \"{}\"", name.value),
+                        name.pos,
+                    ))
+                }
+            }
+        }
+        Ok(refs)
+    }
+
+    fn build_type_ref(&mut self, node: &TypeNode) -> Result<TypeRef, SdlError> {
+        match node {
+            TypeNode::Named(Name { value: name, pos }) => {
+                let kind = match self.kinds.get(name) {
+                    Some(kind) => *kind,
+                    None => {
+                        if BUILT_IN_SCALARS.contains(&name.as_str()) {
+                            if !self.used_scalars.contains(name) {
+                                self.used_scalars.push(name.clone());
+                            }
+                            "SCALAR"
+                        } else {
+                            return Err(error_at(&format!("unknown type \"{}\"", name), *pos));
+                        }
+                    }
+                };
+                Ok(TypeRef {
+                    name: Some(name.clone()),
+                    kind: Some(kind.to_string()),
+                    of_type: None,
+                })
+            }
+            TypeNode::List(inner) => Ok(TypeRef {
+                name: None,
+                kind: Some("LIST".to_string()),
+                of_type: Some(Box::new(self.build_type_ref(inner)?)),
+            }),
+            TypeNode::NonNull(inner) => Ok(TypeRef {
+                name: None,
+                kind: Some("NON_NULL".to_string()),
+                of_type: Some(Box::new(self.build_type_ref(inner)?)),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Schema, SdlError> {
+        parse_sources(&[(None, text)])
+    }
+
+    fn field<'a>(typ: &'a Type, name: &str) -> &'a Field {
+        typ.fields
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|field| field.name.as_deref() == Some(name))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_first_operation_name() {
+        let cases = [
+            ("query IntrospectionQuery {", Some("IntrospectionQuery")),
+            ("# docs\nquery Docs($v: Int) { a }", Some("Docs")),
+            ("query{ a }", None),
+            ("{ a }", None),
+            ("query @live { a }", None),
+            ("mutation Save { a }", Some("Save")),
+            (
+                "fragment F on Query { a }\nquery Docs { ...F }",
+                Some("Docs"),
+            ),
+            ("query Docs { a(tag: \"#1\") }", Some("Docs")),
+            (
+                "fragment F on Query { a(tag: \"\"\"}#\"\"\") }\nquery Docs { ...F }",
+                Some("Docs"),
+            ),
+            ("query Docs { a(tag: \"#1) }", None),
+        ];
+        for (query, name) in cases.iter() {
+            assert_eq!(first_operation_name(query).as_deref(), *name, "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_should_parse_empty_document() {
+        let schema = parse("").unwrap();
+        assert!(schema.query_type.is_none());
+        assert_eq!(0, schema.types.unwrap().len());
+        assert_eq!(0, schema.directives.unwrap().len());
+    }
+
+    #[test]
+    fn test_should_default_root_types_when_no_schema_definition() {
+        let schema = parse(
+            r#"
+            type Query { a: Int }
+            type Mutation { b: Int }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        assert_eq!("Mutation", schema.get_mutation_name().unwrap());
+        assert!(schema.get_subscription_name().is_none());
+    }
+
+    #[test]
+    fn test_should_use_schema_definition_for_root_types() {
+        let schema = parse(
+            r#"
+            schema {
+                query: RootQuery
+                subscription: RootSubscription
+            }
+            type RootQuery { a: Int }
+            type RootSubscription { b: Int }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("RootQuery", schema.get_query_name().unwrap());
+        assert!(schema.get_mutation_name().is_none());
+        assert_eq!("RootSubscription", schema.get_subscription_name().unwrap());
+    }
+
+    #[test]
+    fn test_should_parse_object_fields_and_arguments() {
+        let schema = parse(
+            r#"
+            type Query {
+                players(first: Int = 10, names: [String!]): [Player!]!
+            }
+            type Player { name: String }
+            "#,
+        )
+        .unwrap();
+        let query = schema.get_type("Query").unwrap();
+        assert_eq!("OBJECT", query.kind.as_ref().unwrap());
+        let players = field(query, "players");
+        assert_eq!(
+            "[Player!]!",
+            players.field_type.as_ref().unwrap().decorated_name()
+        );
+        let args = players.args.as_ref().unwrap();
+        assert_eq!(2, args.len());
+        assert_eq!("first", args[0].name.as_ref().unwrap());
+        assert_eq!("10", args[0].default_value.as_ref().unwrap());
+        assert_eq!(
+            "[String!]",
+            args[1].input_type.as_ref().unwrap().decorated_name()
+        );
+        assert!(args[1].default_value.is_none());
+    }
+
+    #[test]
+    fn test_should_resolve_kinds_of_named_types() {
+        let schema = parse(
+            r#"
+            type Query { player: Player, players: [Player] }
+            type Player { name: String }
+            "#,
+        )
+        .unwrap();
+        let query = schema.get_type("Query").unwrap();
+        let player = field(query, "player").field_type.as_ref().unwrap();
+        assert_eq!("OBJECT", player.kind.as_ref().unwrap());
+        let players = field(query, "players").field_type.as_ref().unwrap();
+        assert_eq!(
+            "OBJECT",
+            players.of_type.as_ref().unwrap().kind.as_ref().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_add_only_used_built_in_scalars() {
+        let schema = parse("type Query { a: Int, b: String }").unwrap();
+        let scalars = schema.get_types_of_kind("SCALAR");
+        assert_eq!(2, scalars.len());
+        assert!(schema.get_type("Int").is_some());
+        assert!(schema.get_type("String").is_some());
+        assert!(schema.get_type("Boolean").is_none());
+    }
+
+    #[test]
+    fn test_should_parse_custom_scalar() {
+        let schema = parse(
+            r#"
+            "A point in time"
+            scalar DateTime @specifiedBy(url: "https://example.com")
+            "#,
+        )
+        .unwrap();
+        let typ = schema.get_type("DateTime").unwrap();
+        assert_eq!("SCALAR", typ.kind.as_ref().unwrap());
+        assert_eq!("A point in time", typ.description.as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_should_parse_interfaces_and_implementors() {
+        let schema = parse(
+            r#"
+            interface Node { id: ID! }
+            interface Named implements Node { id: ID!, name: String }
+            type Player implements Node & Named { id: ID!, name: String }
+            type Team implements & Node { id: ID! }
+            "#,
+        )
+        .unwrap();
+        let player = schema.get_type("Player").unwrap();
+        let interfaces = player.interfaces.as_ref().unwrap();
+        assert_eq!(2, interfaces.len());
+        assert_eq!("INTERFACE", interfaces[0].kind.as_ref().unwrap());
+
+        let named = schema.get_type("Named").unwrap();
+        assert_eq!(1, named.interfaces.as_ref().unwrap().len());
+
+        let node = schema.get_type("Node").unwrap();
+        let names: Vec<&str> = node
+            .possible_types
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|typ| typ.name.as_deref().unwrap())
+            .collect();
+        assert_eq!(vec!["Player", "Team"], names);
+    }
+
+    #[test]
+    fn test_should_parse_union() {
+        let schema = parse(
+            r#"
+            type A { a: Int }
+            type B { b: Int }
+            union AorB =
+              | A
+              | B
+            "#,
+        )
+        .unwrap();
+        let union = schema.get_type("AorB").unwrap();
+        assert_eq!("UNION", union.kind.as_ref().unwrap());
+        assert_eq!(2, union.possible_types.as_ref().unwrap().len());
+        assert!(union.fields.is_none());
+    }
+
+    #[test]
+    fn test_should_parse_enum_with_deprecation() {
+        let schema = parse(
+            r#"
+            enum Color {
+                RED
+                "Use RED"
+                CRIMSON @deprecated(reason: "Too dark")
+                MAROON @deprecated
+            }
+            "#,
+        )
+        .unwrap();
+        let color = schema.get_type("Color").unwrap();
+        let values = color.enums.as_ref().unwrap();
+        assert_eq!(3, values.len());
+        assert_eq!(Some(false), values[0].is_deprecated);
+        assert!(values[0].deprecation_reason.is_none());
+        assert_eq!("Use RED", values[1].description.as_ref().unwrap());
+        assert_eq!(Some(true), values[1].is_deprecated);
+        assert_eq!("Too dark", values[1].deprecation_reason.as_ref().unwrap());
+        assert_eq!(
+            DEFAULT_DEPRECATION_REASON,
+            values[2].deprecation_reason.as_ref().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_parse_deprecated_input_value() {
+        let schema = parse(
+            r#"
+            type Query { players(team: ID @deprecated(reason: "Use teamId"), teamId: ID): Int }
+            input Filter { legacy: Int @deprecated }
+            "#,
+        )
+        .unwrap();
+        let query = schema.get_type("Query").unwrap();
+        let args = query.fields.as_ref().unwrap()[0].args.as_ref().unwrap();
+        assert_eq!(Some(true), args[0].is_deprecated);
+        assert_eq!("Use teamId", args[0].deprecation_reason.as_ref().unwrap());
+        assert_eq!(Some(false), args[1].is_deprecated);
+        let filter = schema.get_type("Filter").unwrap();
+        assert_eq!(Some(true), filter.inputs.as_ref().unwrap()[0].is_deprecated);
+    }
+
+    #[test]
+    fn test_should_ignore_deprecated_on_type() {
+        // Types can't be deprecated, only their members
+        let schema = parse(
+            r#"
+            type Coach @deprecated { id: ID }
+            scalar Money
+            extend scalar Money @deprecated(reason: "Use Int")
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            "type Coach {\n  id: ID\n}\n\nscalar Money\n",
+            crate::schema_sdl::schema_to_sdl(&schema)
+        );
+    }
+
+    #[test]
+    fn test_should_parse_deprecated_field() {
+        let schema = parse(r#"type Query { old: Int @deprecated(reason: "Use new") }"#).unwrap();
+        let old = field(schema.get_type("Query").unwrap(), "old");
+        assert_eq!(Some(true), old.is_deprecated);
+        assert_eq!("Use new", old.deprecation_reason.as_ref().unwrap());
+    }
+
+    #[test]
+    fn test_should_parse_input_with_default_values() {
+        let schema = parse(
+            r#"
+            enum Sort { ASC DESC }
+            input Filter {
+                name: String = "a \"b\""
+                sort: Sort = ASC
+                ids: [Int] = [1, 2]
+                nested: Filter = {name: "x", ids: null}
+                enabled: Boolean = true
+                ratio: Float = -1.5e3
+            }
+            "#,
+        )
+        .unwrap();
+        let filter = schema.get_type("Filter").unwrap();
+        assert!(filter.fields.is_none());
+        let defaults: Vec<&str> = filter
+            .inputs
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|input| input.default_value.as_deref().unwrap())
+            .collect();
+        assert_eq!(
+            vec![
+                r#""a \"b\"""#,
+                "ASC",
+                "[1, 2]",
+                r#"{name: "x", ids: null}"#,
+                "true",
+                "-1.5e3"
+            ],
+            defaults
+        );
+    }
+
+    #[test]
+    fn test_should_parse_directive_definition() {
+        let schema = parse(
+            r#"
+            "Marks a field as cached"
+            directive @cached(ttl: Int = 60) repeatable on
+              | FIELD_DEFINITION
+              | OBJECT
+            "#,
+        )
+        .unwrap();
+        let directives = schema.directives.unwrap();
+        assert_eq!(1, directives.len());
+        assert_eq!("cached", directives[0].name.as_ref().unwrap());
+        assert_eq!(
+            "Marks a field as cached",
+            directives[0].description.as_ref().unwrap()
+        );
+        assert_eq!(
+            &vec!["FIELD_DEFINITION".to_string(), "OBJECT".to_string()],
+            directives[0].locations.as_ref().unwrap()
+        );
+        assert_eq!(1, directives[0].args.as_ref().unwrap().len());
+        assert_eq!(Some(true), directives[0].is_repeatable);
+    }
+
+    #[test]
+    fn test_should_parse_block_string_description() {
+        let schema = parse(
+            "\"\"\"\n    A player.\n\n      Indented \\\"\"\" line.\n    \"\"\"\ntype Player { a: Int }",
+        )
+        .unwrap();
+        assert_eq!(
+            "A player.\n\n  Indented \"\"\" line.",
+            schema
+                .get_type("Player")
+                .unwrap()
+                .description
+                .as_ref()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_parse_string_escapes() {
+        let schema = parse(r#""Tab\there é" scalar S"#).unwrap();
+        assert_eq!(
+            "Tab\there \u{e9}",
+            schema.get_type("S").unwrap().description.as_ref().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_ignore_comments_and_commas() {
+        let schema = parse(
+            r#"
+            # The root
+            type Query {
+                a: Int, # first
+                b: Int,
+            }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            2,
+            schema
+                .get_type("Query")
+                .unwrap()
+                .fields
+                .as_ref()
+                .unwrap()
+                .len()
+        );
+    }
+
+    #[test]
+    fn test_should_apply_type_extensions() {
+        let schema = parse(
+            r#"
+            extend type Query { b: Int }
+            type Query { a: Int }
+            interface Node { id: ID! }
+            extend type Query implements Node { id: ID! }
+            enum Color { RED }
+            extend enum Color { BLUE }
+            input Filter { a: Int }
+            extend input Filter { b: Int }
+            type A { a: Int }
+            type B { b: Int }
+            union AorB = A
+            extend union AorB = B
+            scalar Date
+            extend scalar Date @specifiedBy(url: "https://example.com")
+            "#,
+        )
+        .unwrap();
+        let query = schema.get_type("Query").unwrap();
+        assert_eq!(3, query.fields.as_ref().unwrap().len());
+        assert_eq!(1, query.interfaces.as_ref().unwrap().len());
+        let node = schema.get_type("Node").unwrap();
+        assert_eq!(1, node.possible_types.as_ref().unwrap().len());
+        let color = schema.get_type("Color").unwrap();
+        assert_eq!(2, color.enums.as_ref().unwrap().len());
+        let filter = schema.get_type("Filter").unwrap();
+        assert_eq!(2, filter.inputs.as_ref().unwrap().len());
+        let union = schema.get_type("AorB").unwrap();
+        assert_eq!(2, union.possible_types.as_ref().unwrap().len());
+    }
+
+    #[test]
+    fn test_should_apply_schema_extension() {
+        let schema = parse(
+            r#"
+            schema { query: Query }
+            extend schema { mutation: Mutation }
+            type Query { a: Int }
+            type Mutation { b: Int }
+            "#,
+        )
+        .unwrap();
+        assert_eq!("Query", schema.get_query_name().unwrap());
+        assert_eq!("Mutation", schema.get_mutation_name().unwrap());
+    }
+
+    #[test]
+    fn test_should_merge_files() {
+        let files = vec![
+            (
+                PathBuf::from("query.graphql"),
+                "type Query { a: Int }".to_string(),
+            ),
+            (
+                PathBuf::from("players.graphql"),
+                "type Player { name: String }\nextend type Query { players: [Player] }".to_string(),
+            ),
+        ];
+        let schema = parse_files(&files).unwrap();
+        let query = schema.get_type("Query").unwrap();
+        assert_eq!(2, query.fields.as_ref().unwrap().len());
+        assert_eq!(
+            "OBJECT",
+            query.fields.as_ref().unwrap()[1]
+                .field_type
+                .as_ref()
+                .unwrap()
+                .of_type
+                .as_ref()
+                .unwrap()
+                .kind
+                .as_ref()
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_should_report_file_of_conflicting_definition() {
+        let files = vec![
+            (
+                PathBuf::from("a.graphql"),
+                "type Query { a: Int }".to_string(),
+            ),
+            (
+                PathBuf::from("b.graphql"),
+                "scalar Date\ntype Query { b: Int }".to_string(),
+            ),
+        ];
+        let err = parse_files(&files).unwrap_err();
+        assert_eq!("type \"Query\" is defined more than once", err.message);
+        assert_eq!(Some(PathBuf::from("b.graphql")), err.path);
+        assert_eq!((2, 6), (err.line, err.column));
+        assert!(err.to_string().contains("2 | type Query { b: Int }"));
+    }
+
+    #[test]
+    fn test_should_fail_when_extending_undefined_type() {
+        let err = parse_error("extend type Query { a: Int }");
+        assert_eq!("cannot extend undefined type \"Query\"", err.message);
+        assert_eq!((1, 13), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_extending_wrong_kind() {
+        let err = parse_error("type Filter { a: Int }\nextend input Filter { b: Int }");
+        assert_eq!(
+            "cannot extend \"Filter\": it is an object type, not an input type",
+            err.message
+        );
+        assert_eq!((2, 14), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_extension_redefines_field() {
+        let err = parse_error("type Query { a: Int }\nextend type Query { a: String }");
+        assert_eq!("field \"Query.a\" is already defined", err.message);
+        assert_eq!((2, 21), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_field_defined_twice() {
+        let err = parse_error("type Query {\n  a: Int\n  a: Int\n}");
+        assert_eq!("field \"Query.a\" is already defined", err.message);
+        assert_eq!((3, 3), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_extension_redefines_enum_value() {
+        let err = parse_error("enum Color { RED }\nextend enum Color { RED }");
+        assert_eq!("enum value \"Color.RED\" is already defined", err.message);
+    }
+
+    #[test]
+    fn test_should_fail_when_extension_repeats_union_member() {
+        let err = parse_error("type A { a: Int }\nunion U = A\nextend union U = A");
+        assert_eq!("union \"U\" already includes \"A\"", err.message);
+    }
+
+    #[test]
+    fn test_should_fail_when_extension_repeats_interface() {
+        let err = parse_error(
+            "interface Node { id: ID }\ntype A implements Node { id: ID }\nextend type A implements Node",
+        );
+        assert_eq!("type \"A\" already implements \"Node\"", err.message);
+    }
+
+    #[test]
+    fn test_should_fail_when_schema_defined_twice() {
+        let err =
+            parse_error("type Query { a: Int }\nschema { query: Query }\nschema { query: Query }");
+        assert_eq!("schema is defined more than once", err.message);
+        assert_eq!((3, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_schema_extension_redefines_operation() {
+        let err = parse_error(
+            "type Query { a: Int }\nschema { query: Query }\nextend schema { query: Query }",
+        );
+        assert_eq!("schema already defines a query type", err.message);
+    }
+
+    #[test]
+    fn test_should_fail_when_directive_defined_twice() {
+        let err = parse_error("directive @a on FIELD\ndirective @a on FIELD");
+        assert_eq!("directive \"@a\" is defined more than once", err.message);
+    }
+
+    #[test]
+    fn test_should_fail_when_extension_has_description() {
+        let err = parse_error("type Query { a: Int }\n\"Nope\" extend type Query { b: Int }");
+        assert_eq!("extensions cannot have descriptions", err.message);
+    }
+
+    fn parse_error(text: &str) -> SdlError {
+        match parse(text) {
+            Ok(_) => panic!("schema should fail to parse"),
+            Err(err) => err,
+        }
+    }
+
+    #[test]
+    fn test_should_fail_when_unknown_type() {
+        let err = parse_error("type Query {\n  a: Missing\n}");
+        assert_eq!("unknown type \"Missing\"", err.message);
+        assert_eq!((2, 6), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_unknown_interface() {
+        let err = parse_error("type Player implements Node { a: Int }");
+        assert_eq!("unknown type \"Node\"", err.message);
+        assert_eq!((1, 24), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_type_defined_twice() {
+        let err = parse_error("scalar A\nscalar A");
+        assert_eq!("type \"A\" is defined more than once", err.message);
+        assert_eq!((2, 8), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_missing_colon() {
+        let err = parse_error("type Query {\n  a Int\n}");
+        assert_eq!("expected \":\", found name \"Int\"", err.message);
+        assert_eq!((2, 5), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_unterminated_string() {
+        let err = parse_error("\"oops\ntype Query { a: Int }");
+        assert_eq!("unterminated string", err.message);
+        assert_eq!((1, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_not_a_definition() {
+        let err = parse_error("query { a }");
+        assert_eq!("expected definition, found name \"query\"", err.message);
+        assert_eq!((1, 1), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_should_fail_when_unexpected_character() {
+        let err = parse_error("type Query { a: Int% }");
+        assert_eq!("unexpected character '%'", err.message);
+        assert_eq!((1, 20), (err.line, err.column));
+    }
+
+    #[test]
+    fn test_error_should_underline_token_with_caret() {
+        let err = parse_error("type Query {\n  a: Missing\n}");
+        assert_eq!(
+            r#"unknown type "Missing"
+ --> <schema>:2:6
+  |
+2 |   a: Missing
+  |      ^^^^^^^"#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_should_include_path_when_set() {
+        let files = vec![(
+            PathBuf::from("a.graphql"),
+            "type Query {\n  a: Missing\n}".to_string(),
+        )];
+        let err = parse_files(&files).unwrap_err();
+        assert!(err.to_string().contains(" --> a.graphql:2:6\n"));
+    }
+
+    #[test]
+    fn test_error_should_widen_gutter_for_long_line_numbers() {
+        let text = format!("{}type Query {{ a: Missing }}", "\n".repeat(9));
+        let err = parse_error(&text);
+        assert_eq!(
+            r#"unknown type "Missing"
+  --> <schema>:10:17
+   |
+10 | type Query { a: Missing }
+   |                 ^^^^^^^"#,
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_should_preserve_tabs_when_underlining() {
+        let err = parse_error("type Query {\n\ta: Missing\n}");
+        assert!(err.to_string().ends_with("\n  | \t   ^^^^^^^"));
+    }
+
+    #[test]
+    fn test_error_should_point_past_end_of_file() {
+        let err = parse_error("type Query {");
+        assert_eq!("expected name, found end of file", err.message);
+        assert!(err
+            .to_string()
+            .ends_with("1 | type Query {\n  |             ^"));
+    }
+}